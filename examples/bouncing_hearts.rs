@@ -8,11 +8,11 @@ use ti::{
 #[derive(Clone)]
 struct Heart {
     pub sprite: Sprite,
-    pub max_x: u16,
-    pub max_y: u16,
-    pub x: u16,
+    pub max_x: i32,
+    pub max_y: i32,
+    pub x: i32,
     pub right: bool,
-    pub y: u16,
+    pub y: i32,
     pub down: bool,
     pub slowness: u64,
 }
@@ -47,9 +47,9 @@ impl Heart {
 }
 
 fn main() {
-    let width = 64;
-    let height = 35;
-    let mut screen = Screen::new_pixels(width, height);
+    let width: i32 = 64;
+    let height: i32 = 35;
+    let mut screen = Screen::new_pixels(width as u16, height as u16);
 
     let sprite =
         Sprite::rgb_from_image_path("examples/heart.png", 1, true, 2).expect("png reading failure");
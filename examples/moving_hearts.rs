@@ -6,8 +6,8 @@ use ti::{
 };
 
 fn main() {
-    let max = 16;
-    let mut screen = Screen::new_pixels(16 + max * 2, 16 + max * 2);
+    let max: i32 = 16;
+    let mut screen = Screen::new_pixels(16 + max as u16 * 2, 16 + max as u16 * 2);
     screen.enter_screen().unwrap();
 
     let sprite =
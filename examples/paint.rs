@@ -0,0 +1,35 @@
+//! Draw on the screen with the mouse. Left click paints, right click erases, Esc exits.
+use std::{io, ops::ControlFlow};
+
+use ti::{
+    backend::CrosstermBackend,
+    event::{Event, Key, MouseButton, MouseKind},
+    frame::LoopConfig,
+    screen::{Blit, Screen},
+};
+
+fn main() -> io::Result<()> {
+    let mut backend = CrosstermBackend::stdout().with_mouse_capture(true);
+    let mut screen = Screen::new_for_backend(&backend)?;
+    screen.run_loop_with(&mut backend, LoopConfig::default(), |s, frame| {
+        for event in &frame.events {
+            match event {
+                Event::Mouse(mouse) => match mouse.kind {
+                    MouseKind::Down(MouseButton::Left) | MouseKind::Drag(MouseButton::Left) => {
+                        s.draw_pixel(mouse.x, mouse.y, Blit::Set);
+                    }
+                    MouseKind::Down(MouseButton::Right) | MouseKind::Drag(MouseButton::Right) => {
+                        s.draw_pixel(mouse.x, mouse.y, Blit::Unset);
+                    }
+                    _ => (),
+                },
+                Event::Resize { width, height } => s.resize(*width, *height),
+                event if event.pressed() == Some(Key::Esc) => return Ok(ControlFlow::Break(())),
+                _ => (),
+            }
+        }
+        s.draw_str("Left: paint, right: erase, Esc: exit", 0, 0, None);
+        Ok(ControlFlow::Continue(()))
+    })?;
+    Ok(())
+}
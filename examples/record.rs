@@ -0,0 +1,42 @@
+use std::{ops::ControlFlow, time::Duration};
+
+use ti::{
+    backend::{MemoryBackend, Recorder},
+    color::Color,
+    frame::LoopConfig,
+    screen::{Blit, Screen},
+};
+
+/// Records a few seconds of a bouncing ball to `ball.cast`, without needing a terminal.
+/// Play it back with `asciinema play ball.cast`.
+fn main() {
+    let width: i32 = 80;
+    let height: i32 = 40;
+    let mut screen = Screen::new_pixels(width as u16, height as u16);
+    let mut recorder = Recorder::create(
+        "ball.cast",
+        MemoryBackend::new(screen.width(), screen.height()),
+    )
+    .expect("failed to create recording");
+
+    let (mut x, mut y, mut dx, mut dy) = (10, 10, 1, 1);
+    screen
+        .run_loop_with(&mut recorder, LoopConfig::new(30.), |s, frame| {
+            if frame.elapsed > Duration::from_secs(5) {
+                return Ok(ControlFlow::Break(()));
+            }
+            s.clear();
+            s.draw_rect(0, 0, width, height, Blit::Set, None);
+            s.fill_circle(x, y, 4, Blit::Set, Some(Color::rgb(255, 96, 64)));
+            if !(5..width - 5).contains(&(x + dx)) {
+                dx = -dx;
+            }
+            if !(5..height - 5).contains(&(y + dy)) {
+                dy = -dy;
+            }
+            x += dx;
+            y += dy;
+            Ok(ControlFlow::Continue(()))
+        })
+        .expect("failed to record");
+}
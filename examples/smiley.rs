@@ -24,7 +24,7 @@ fn main() -> io::Result<()> {
     Ok(())
 }
 
-fn draw_smiley(screen: &mut Screen, x: u16, y: u16, blit: Blit) {
+fn draw_smiley(screen: &mut Screen, x: i32, y: i32, blit: Blit) {
     let smiley = Sprite::from_braille_string(&["⢌⣈⠄"], Some(standard::GREEN), 0).unwrap();
     screen.draw_sprite(&smiley, x, y, blit);
 }
@@ -1,13 +1,15 @@
+use std::ops::ControlFlow;
+
 use ti::{
-    event::Direction,
+    frame::LoopConfig,
     screen::{Blit, Screen},
     sprite::Sprite,
 };
 
 fn main() {
-    let width = 128;
-    let height = 64;
-    let mut screen = Screen::new_pixels(width, height);
+    let width: i32 = 128;
+    let height: i32 = 64;
+    let mut screen = Screen::new_pixels(width as u16, height as u16);
 
     let sprite =
         Sprite::rgb_from_image_path("examples/heart.png", 2, true, 0).expect("png reading failure");
@@ -15,7 +17,7 @@ fn main() {
     let mut x = 5;
     let mut y = 4;
     screen
-        .start_loop(60, |s, event| {
+        .run_loop(LoopConfig::new(60.), |s, frame| {
             s.clear();
             for y in 3..height - 3 {
                 s.draw_pixel_colored(1, y, Blit::Set, None);
@@ -26,15 +28,12 @@ fn main() {
                 s.draw_pixel_colored(x, height - 4, Blit::Set, None);
             }
             s.draw_sprite(&sprite, x, y, Blit::Set);
-            match event.and_then(|e| e.direction_wasd()) {
-                // magic numbers based on sprite shape
-                Some(Direction::Right) => x = x.saturating_add(1).clamp(2, width - 34),
-                Some(Direction::Left) => x = x.saturating_sub(1).clamp(2, width - 34),
-                Some(Direction::Down) => y = y.saturating_add(1).clamp(2, height - 34),
-                Some(Direction::Up) => y = y.saturating_sub(1).clamp(2, height - 34),
-                None => (),
-            }
-            Ok(())
+            // Moves every frame while the keys are held, including diagonally
+            let (dx, dy) = frame.input.direction_vector_wasd();
+            // magic numbers based on sprite shape
+            x = (x + dx).clamp(2, width - 34);
+            y = (y + dy).clamp(2, height - 34);
+            Ok(ControlFlow::<()>::Continue(()))
         })
         .unwrap();
 }
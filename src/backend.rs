@@ -0,0 +1,918 @@
+//! Output backends that a [`crate::screen::Screen`] can render to.
+//!
+//! A [`Backend`] receives a stream of simple drawing operations (cursor movement, colors and text)
+//! and is responsible for turning them into output. The [`CrosstermBackend`] writes ANSI escapes to
+//! any [`Write`] implementation, typically the standard output, while the [`MemoryBackend`] keeps
+//! an in-memory grid of characters that can be inspected, e.g. in tests.
+//!
+//! A [`Recorder`] wraps another backend and records everything rendered to it as an asciicast file.
+//!
+//! A [`Session`] keeps a backend entered for as long as it is alive, and exits it when dropped,
+//! even on early returns or panics.
+
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    fs::File,
+    io::BufWriter,
+    io::{self, Stdout, Write},
+    ops::{Deref, DerefMut},
+    panic,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Once,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, MoveToColumn, MoveToRow, Show},
+    event::{
+        poll, read, DisableMouseCapture, EnableMouseCapture, Event as CrosstermEvent,
+        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    style::{self, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{
+        self, disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, Clear, ClearType,
+        EnterAlternateScreen, LeaveAlternateScreen,
+    },
+    QueueableCommand,
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{
+    color::{truecolor_supported, Color},
+    units::{cell_length, index},
+};
+
+/// A destination for rendered frames.
+///
+/// The operations are issued in order by [`crate::screen::Screen::render_to()`]; implementations
+/// may buffer them until [`Backend::flush()`] is called.
+pub trait Backend {
+    /// Prepares the backend for drawing, e.g. by entering the terminal's alternate screen.
+    fn enter(&mut self) -> io::Result<()>;
+    /// Restores the backend to its original state, undoing [`Backend::enter()`].
+    fn exit(&mut self) -> io::Result<()>;
+    /// Moves the cursor to the given cell position.
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()>;
+    /// Sets the foreground color used for the following text. `None` resets it to the default color.
+    fn set_color(&mut self, color: Option<Color>) -> io::Result<()>;
+    /// Sets the background color used for the following text. `None` resets it to the default color.
+    fn set_background(&mut self, color: Option<Color>) -> io::Result<()>;
+    /// Writes text at the cursor position, advancing the cursor.
+    fn write(&mut self, text: &str) -> io::Result<()>;
+    /// Clears all output, e.g. after the terminal was resized and its contents may have been reflowed.
+    fn clear(&mut self) -> io::Result<()>;
+    /// Flushes any buffered output, presenting the frame.
+    fn flush(&mut self) -> io::Result<()>;
+    /// Returns the size of the output area, in cells.
+    fn size(&self) -> io::Result<(u16, u16)>;
+    /// Waits up to `timeout` for an input event. Returns `None` if no event arrived in time.
+    fn poll_event(&mut self, timeout: Duration) -> io::Result<Option<CrosstermEvent>>;
+}
+
+/// A guard keeping a [`Backend`] entered while it is alive.
+///
+/// [`Session::enter()`] calls [`Backend::enter()`], and dropping the session calls [`Backend::exit()`].
+/// This way the terminal is restored when the session goes out of scope for any reason, including
+/// `?` returns and panics. Errors while exiting on drop are ignored; use [`Session::exit()`]
+/// to handle them.
+///
+/// The session dereferences to the backend, so it can be used for rendering directly.
+///
+/// # Examples
+///
+/// ```
+/// use ti::backend::{MemoryBackend, Session};
+/// use ti::screen::Screen;
+///
+/// let mut backend = MemoryBackend::new(2, 2);
+/// {
+///     let mut session = Session::enter(&mut backend).unwrap();
+///     Screen::new_cells(2, 2).render_to(&mut *session).unwrap();
+///     assert!(session.is_entered());
+/// }
+/// assert!(!backend.is_entered());
+/// ```
+#[derive(Debug)]
+pub struct Session<'a, B: Backend + ?Sized> {
+    backend: &'a mut B,
+    entered: bool,
+}
+
+impl<'a, B: Backend + ?Sized> Session<'a, B> {
+    /// Enters the backend, returning a guard that exits it when dropped.
+    pub fn enter(backend: &'a mut B) -> io::Result<Self> {
+        backend.enter()?;
+        Ok(Self {
+            backend,
+            entered: true,
+        })
+    }
+
+    /// Exits the backend, returning any error that occurs while doing so.
+    pub fn exit(mut self) -> io::Result<()> {
+        self.entered = false;
+        self.backend.exit()
+    }
+}
+
+impl<B: Backend + ?Sized> Deref for Session<'_, B> {
+    type Target = B;
+
+    fn deref(&self) -> &Self::Target {
+        self.backend
+    }
+}
+
+impl<B: Backend + ?Sized> DerefMut for Session<'_, B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.backend
+    }
+}
+
+impl<B: Backend + ?Sized> Drop for Session<'_, B> {
+    fn drop(&mut self) {
+        if self.entered {
+            let _ = self.backend.exit();
+        }
+    }
+}
+
+/// Whether a [`CrosstermBackend`] has changed the terminal's state, and not restored it yet.
+static TERMINAL_ENTERED: AtomicBool = AtomicBool::new(false);
+/// Whether the kitty keyboard protocol flags have been pushed, and not popped yet. Like the terminal
+/// itself, this is shared by all backends.
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+static PANIC_HOOK: Once = Once::new();
+
+/// Restores the terminal to its normal state, in case a [`CrosstermBackend`] has entered
+/// it and not exited yet. This leaves raw mode and the alternate screen, disables mouse capture
+/// and shows the cursor again. Does nothing otherwise.
+///
+/// This is called automatically on panics, before the panic message is printed.
+/// See [`install_panic_hook()`].
+pub fn restore_terminal() -> io::Result<()> {
+    if !TERMINAL_ENTERED.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let mut stdout = io::stdout();
+    if KEYBOARD_ENHANCED.swap(false, Ordering::SeqCst) {
+        stdout.queue(PopKeyboardEnhancementFlags)?;
+    }
+    stdout.queue(DisableMouseCapture)?;
+    disable_raw_mode()?;
+    stdout
+        .queue(ResetColor)?
+        .queue(LeaveAlternateScreen)?
+        .queue(Show)?;
+    stdout.flush()
+}
+
+/// Installs a panic hook that calls [`restore_terminal()`] before running the previously installed
+/// hook. Without it, the panic message would be printed to the alternate screen in raw mode,
+/// and the shell would be left unusable.
+///
+/// This is called by [`CrosstermBackend`] when entering the terminal, so it rarely needs to be called
+/// manually. The hook is only installed once, no matter how many times this is called. If you install
+/// your own panic hook after entering the terminal, call [`restore_terminal()`] from it.
+pub fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = restore_terminal();
+            previous(info);
+        }));
+    });
+}
+
+/// A [`Backend`] writing ANSI escape sequences to a writer using [`crossterm`].
+///
+/// Input events are read from the terminal that the process is attached to. If the terminal
+/// supports the kitty keyboard protocol, it is enabled while the backend is entered, so that
+/// key repeats and releases are reported as well.
+///
+/// RGB colors are written as 24-bit "truecolor" escapes if the terminal supports them
+/// (see [`crate::color::truecolor_supported()`]), and approximated by ANSI colors otherwise.
+/// This can be overridden with [`CrosstermBackend::with_truecolor()`].
+///
+/// Mouse events are only reported if mouse capture is enabled
+/// with [`CrosstermBackend::with_mouse_capture()`].
+///
+/// Entering the backend installs a panic hook restoring the terminal (see [`install_panic_hook()`]).
+/// Use a [`Session`] to also restore it on early returns.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ti::backend::CrosstermBackend;
+/// use ti::screen::Screen;
+///
+/// let mut screen = Screen::new_cells(2, 2);
+/// let mut backend = CrosstermBackend::stdout();
+/// screen.render_to(&mut backend).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct CrosstermBackend<W: Write = Stdout> {
+    writer: W,
+    cursor: Option<(u16, u16)>,
+    truecolor: bool,
+    mouse_capture: bool,
+}
+
+impl CrosstermBackend {
+    /// Creates a backend writing to the standard output.
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+}
+
+impl<W: Write> CrosstermBackend<W> {
+    /// Creates a backend writing to the given writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            cursor: None,
+            truecolor: truecolor_supported(),
+            mouse_capture: false,
+        }
+    }
+
+    /// Sets whether RGB colors are written as truecolor escapes. When `false`,
+    /// they are approximated by the closest ANSI colors instead.
+    pub fn with_truecolor(mut self, truecolor: bool) -> Self {
+        self.truecolor = truecolor;
+        self
+    }
+
+    /// Returns whether RGB colors are written as truecolor escapes.
+    pub const fn truecolor(&self) -> bool {
+        self.truecolor
+    }
+
+    /// Sets whether mouse events are captured while the backend is entered. This is off by default,
+    /// because capturing the mouse prevents selecting text in most terminals.
+    pub fn with_mouse_capture(mut self, mouse_capture: bool) -> Self {
+        self.mouse_capture = mouse_capture;
+        self
+    }
+
+    /// Returns whether mouse events are captured while the backend is entered.
+    pub const fn mouse_capture(&self) -> bool {
+        self.mouse_capture
+    }
+
+    /// Converts a color to be written, resetting to the default color on `None`.
+    fn crossterm_color(&self, color: Option<Color>) -> style::Color {
+        match color {
+            Some(color) if self.truecolor => color.to_crossterm_color(),
+            Some(color) => color.to_ansi().to_crossterm_color(),
+            None => style::Color::Reset,
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Consumes the backend, returning the underlying writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Backend for CrosstermBackend<W> {
+    fn enter(&mut self) -> io::Result<()> {
+        install_panic_hook();
+        TERMINAL_ENTERED.store(true, Ordering::SeqCst);
+        self.writer.queue(EnterAlternateScreen)?.queue(Hide)?;
+        if self.mouse_capture {
+            self.writer.queue(EnableMouseCapture)?;
+        }
+        enable_raw_mode()?;
+        if supports_keyboard_enhancement().unwrap_or(false) {
+            KEYBOARD_ENHANCED.store(true, Ordering::SeqCst);
+            self.writer.queue(PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_EVENT_TYPES,
+            ))?;
+        }
+        self.writer.flush()?;
+        self.cursor = None;
+        Ok(())
+    }
+
+    fn exit(&mut self) -> io::Result<()> {
+        // The terminal may have been restored already, e.g. by the panic hook
+        if !TERMINAL_ENTERED.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        // The flags may have been pushed by another backend, e.g. by `Screen::enter_screen()`
+        if KEYBOARD_ENHANCED.swap(false, Ordering::SeqCst) {
+            self.writer.queue(PopKeyboardEnhancementFlags)?;
+        }
+        if self.mouse_capture {
+            self.writer.queue(DisableMouseCapture)?;
+        }
+        disable_raw_mode()?;
+        self.writer.queue(LeaveAlternateScreen)?.queue(Show)?;
+        self.writer.flush()?;
+        TERMINAL_ENTERED.store(false, Ordering::SeqCst);
+        self.cursor = None;
+        Ok(())
+    }
+
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()> {
+        // Pick the shortest escape sequence for the movement
+        match self.cursor {
+            Some((cur_x, cur_y)) if (cur_x, cur_y) == (x, y) => (),
+            Some((cur_x, _)) if cur_x == x => {
+                self.writer.queue(MoveToRow(y))?;
+            }
+            Some((_, cur_y)) if cur_y == y => {
+                self.writer.queue(MoveToColumn(x))?;
+            }
+            _ => {
+                self.writer.queue(MoveTo(x, y))?;
+            }
+        }
+        self.cursor = Some((x, y));
+        Ok(())
+    }
+
+    fn set_color(&mut self, color: Option<Color>) -> io::Result<()> {
+        let color = self.crossterm_color(color);
+        self.writer.queue(SetForegroundColor(color))?;
+        Ok(())
+    }
+
+    fn set_background(&mut self, color: Option<Color>) -> io::Result<()> {
+        let color = self.crossterm_color(color);
+        self.writer.queue(SetBackgroundColor(color))?;
+        Ok(())
+    }
+
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        self.writer.write_all(text.as_bytes())?;
+        if let Some((x, _)) = &mut self.cursor {
+            *x = x.saturating_add(text.width() as u16);
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.writer.queue(Clear(ClearType::All))?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn size(&self) -> io::Result<(u16, u16)> {
+        terminal::size()
+    }
+
+    fn poll_event(&mut self, timeout: Duration) -> io::Result<Option<CrosstermEvent>> {
+        if poll(timeout)? {
+            read().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// A [`Backend`] that draws into an in-memory character grid instead of a terminal.
+///
+/// Useful for testing, or for running a [`crate::screen::Screen`] without a terminal attached.
+/// Text written outside of the grid is discarded.
+///
+/// # Examples
+///
+/// ```
+/// use ti::backend::MemoryBackend;
+/// use ti::screen::{Blit, Screen};
+///
+/// let mut screen = Screen::new_cells(2, 1);
+/// let mut backend = MemoryBackend::new(2, 1);
+/// screen.draw_pixel(0, 0, Blit::Set);
+/// screen.render_to(&mut backend).unwrap();
+/// assert_eq!(backend.contents(), screen.rasterize());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MemoryBackend {
+    /// `None` for cells covered by a wide character to their left.
+    chars: Vec<Option<char>>,
+    colors: Vec<Option<Color>>,
+    backgrounds: Vec<Option<Color>>,
+    width: u16,
+    height: u16,
+    cursor: (u16, u16),
+    color: Option<Color>,
+    background: Option<Color>,
+    entered: bool,
+    events: VecDeque<Option<CrosstermEvent>>,
+}
+
+impl MemoryBackend {
+    /// Creates a new backend with a grid of the given size in cells, filled with blank braille characters.
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            chars: vec![Some('\u{2800}'); cell_length(width, height)],
+            colors: vec![None; cell_length(width, height)],
+            backgrounds: vec![None; cell_length(width, height)],
+            width,
+            height,
+            cursor: (0, 0),
+            color: None,
+            background: None,
+            entered: false,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Returns the width of the grid, in cells.
+    pub const fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Returns the height of the grid, in cells.
+    pub const fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the character drawn at the given cell position, or `None` if out of bounds
+    /// or covered by a wide character to its left.
+    pub fn char_at(&self, x: u16, y: u16) -> Option<char> {
+        if x < self.width && y < self.height {
+            self.chars[index(x, y, self.width)]
+        } else {
+            None
+        }
+    }
+
+    /// Returns the foreground color of the given cell position, or `None` if out of bounds or uncolored.
+    pub fn color_at(&self, x: u16, y: u16) -> Option<Color> {
+        if x < self.width && y < self.height {
+            self.colors[index(x, y, self.width)]
+        } else {
+            None
+        }
+    }
+
+    /// Returns the background color of the given cell position, or `None` if out of bounds or uncolored.
+    pub fn background_at(&self, x: u16, y: u16) -> Option<Color> {
+        if x < self.width && y < self.height {
+            self.backgrounds[index(x, y, self.width)]
+        } else {
+            None
+        }
+    }
+
+    /// Returns the contents of the grid as text, with a newline after each row.
+    ///
+    /// This uses the same format as [`crate::screen::Screen::rasterize()`].
+    pub fn contents(&self) -> String {
+        let mut s = String::with_capacity(self.chars.len() * 3 + self.height as usize);
+        for row in self.chars.chunks(self.width.max(1) as usize) {
+            s.extend(row.iter().flatten());
+            s.push('\n');
+        }
+        s
+    }
+
+    /// Returns whether the backend is currently between calls to [`Backend::enter()`] and [`Backend::exit()`].
+    pub const fn is_entered(&self) -> bool {
+        self.entered
+    }
+
+    /// Queues an input event to be returned by [`Backend::poll_event()`].
+    pub fn push_event(&mut self, event: CrosstermEvent) {
+        self.events.push_back(Some(event));
+    }
+
+    /// Queues a timeout, i.e. the next call to [`Backend::poll_event()`] returns `None` even
+    /// if more events are queued after it. This can be used to spread events over multiple frames
+    /// of a rendering loop.
+    pub fn push_timeout(&mut self) {
+        self.events.push_back(None);
+    }
+
+    /// Resizes the grid, clearing its contents. Like a terminal, this queues
+    /// a resize event to be returned by [`Backend::poll_event()`].
+    pub fn resize(&mut self, width: u16, height: u16) {
+        *self = Self {
+            events: std::mem::take(&mut self.events),
+            entered: self.entered,
+            ..Self::new(width, height)
+        };
+        self.push_event(CrosstermEvent::Resize(width, height));
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn enter(&mut self) -> io::Result<()> {
+        self.entered = true;
+        Ok(())
+    }
+
+    fn exit(&mut self) -> io::Result<()> {
+        self.entered = false;
+        Ok(())
+    }
+
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.cursor = (x, y);
+        Ok(())
+    }
+
+    fn set_color(&mut self, color: Option<Color>) -> io::Result<()> {
+        self.color = color;
+        Ok(())
+    }
+
+    fn set_background(&mut self, color: Option<Color>) -> io::Result<()> {
+        self.background = color;
+        Ok(())
+    }
+
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        for c in text.chars() {
+            // Like a terminal, wide characters cover two cells
+            let width = c.width().unwrap_or(0) as u16;
+            if width == 0 {
+                continue;
+            }
+            let (x, y) = self.cursor;
+            let end = x.saturating_add(width).min(self.width);
+            if x < self.width && y < self.height {
+                // Overwriting half of a wide character erases the other half
+                let (first, last) = (index(x, y, self.width), index(end - 1, y, self.width));
+                if x > 0 && self.chars[first].is_none() {
+                    self.chars[first - 1] = Some(' ');
+                }
+                if end < self.width && self.chars[last + 1].is_none() {
+                    self.chars[last + 1] = Some(' ');
+                }
+                for i in first..=last {
+                    self.chars[i] = (i == first).then_some(c);
+                    self.colors[i] = self.color;
+                    self.backgrounds[i] = self.background;
+                }
+            }
+            self.cursor.0 = x.saturating_add(width);
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.chars.fill(Some('\u{2800}'));
+        self.colors.fill(None);
+        self.backgrounds.fill(None);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn size(&self) -> io::Result<(u16, u16)> {
+        Ok((self.width, self.height))
+    }
+
+    fn poll_event(&mut self, _timeout: Duration) -> io::Result<Option<CrosstermEvent>> {
+        Ok(self.events.pop_front().flatten())
+    }
+}
+
+/// A [`Backend`] recording its output to an [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/)
+/// file, which can be played back with `asciinema play` or embedded in web pages with asciinema-player.
+///
+/// All operations are forwarded to an inner backend, so a recording can be made while displaying
+/// to the terminal by wrapping a [`CrosstermBackend`]. To record without a terminal, wrap a
+/// [`MemoryBackend`] instead (see [`Recorder::headless()`]).
+///
+/// The operations are also encoded as ANSI escapes, which are written as an output event each time
+/// the backend is flushed, i.e. once per rendered frame. Event times are measured from the creation
+/// of the recorder. Colors are always recorded as truecolor escapes. Resize events polled from
+/// the inner backend are recorded as well; other input events are not.
+///
+/// # Examples
+///
+/// Recording a rendering loop while displaying it:
+///
+/// ```no_run
+/// use ti::backend::{CrosstermBackend, Recorder};
+/// use ti::screen::{Blit, Screen};
+///
+/// let mut recorder = Recorder::create("demo.cast", CrosstermBackend::stdout()).unwrap();
+/// let mut screen = Screen::new_cells(20, 10);
+/// screen.start_loop_with(&mut recorder, 30, |s, _events| {
+///     s.draw_pixel(0, 0, Blit::Toggle);
+///     Ok(())
+/// }).unwrap();
+/// ```
+///
+/// Recording without a terminal:
+///
+/// ```
+/// use ti::backend::{Backend, Recorder};
+/// use ti::screen::{Blit, Screen};
+///
+/// let mut recorder = Recorder::headless(2, 1, vec![]).unwrap();
+/// let mut screen = Screen::new_cells(2, 1);
+/// screen.draw_pixel(0, 0, Blit::Set);
+/// screen.render_to(&mut recorder).unwrap();
+///
+/// let (backend, cast) = recorder.into_inner();
+/// assert_eq!(backend.contents(), "⠁⠀\n");
+/// let cast = String::from_utf8(cast).unwrap();
+/// let mut lines = cast.lines();
+/// assert!(lines.next().unwrap().starts_with(r#"{"version": 2, "width": 2, "height": 1"#));
+/// assert!(lines.next().unwrap().ends_with(r#", "o", "\u001b[1;1H\u001b[39m\u001b[49m⠁"]"#));
+/// ```
+#[derive(Debug)]
+pub struct Recorder<B: Backend, W: Write> {
+    inner: B,
+    writer: W,
+    encoder: CrosstermBackend<Vec<u8>>,
+    start: Instant,
+}
+
+impl<B: Backend> Recorder<B, BufWriter<File>> {
+    /// Creates a recorder writing to a new file at the given path, replacing it if it exists.
+    pub fn create<P: AsRef<Path>>(path: P, inner: B) -> io::Result<Self> {
+        Self::new(inner, BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> Recorder<MemoryBackend, W> {
+    /// Creates a recorder that doesn't need a terminal, drawing into a [`MemoryBackend`] of
+    /// the given size in cells.
+    pub fn headless(width: u16, height: u16, writer: W) -> io::Result<Self> {
+        Self::new(MemoryBackend::new(width, height), writer)
+    }
+}
+
+impl<B: Backend, W: Write> Recorder<B, W> {
+    /// Creates a recorder forwarding to the given backend, and writing the recording to the writer.
+    ///
+    /// The header of the recording is written immediately, using the current size of the backend.
+    pub fn new(inner: B, mut writer: W) -> io::Result<Self> {
+        let (width, height) = inner.size()?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        writeln!(
+            writer,
+            r#"{{"version": 2, "width": {width}, "height": {height}, "timestamp": {timestamp}, "env": {{"TERM": "xterm-256color"}}}}"#
+        )?;
+        Ok(Self {
+            inner,
+            writer,
+            encoder: CrosstermBackend::new(vec![]).with_truecolor(true),
+            start: Instant::now(),
+        })
+    }
+
+    /// Returns a reference to the inner backend.
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner backend.
+    pub fn inner_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    /// Consumes the recorder, returning the inner backend and the writer of the recording.
+    ///
+    /// Output that hasn't been flushed yet is not recorded.
+    pub fn into_inner(self) -> (B, W) {
+        (self.inner, self.writer)
+    }
+
+    /// Writes an event with the given type and data to the recording.
+    fn write_event(&mut self, kind: &str, data: &str) -> io::Result<()> {
+        let time = self.start.elapsed().as_secs_f64();
+        writeln!(
+            self.writer,
+            "[{time:.6}, \"{kind}\", {}]",
+            json_string(data)
+        )?;
+        self.writer.flush()
+    }
+
+    /// Writes the output encoded since the last call as an output event, if there is any.
+    fn write_output(&mut self) -> io::Result<()> {
+        if self.encoder.writer.is_empty() {
+            return Ok(());
+        }
+        let output = std::mem::take(&mut self.encoder.writer);
+        self.write_event("o", &String::from_utf8_lossy(&output))
+    }
+}
+
+/// Quotes a string as a JSON string literal.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+impl<B: Backend, W: Write> Backend for Recorder<B, W> {
+    fn enter(&mut self) -> io::Result<()> {
+        self.inner.enter()?;
+        self.encoder
+            .writer
+            .queue(EnterAlternateScreen)?
+            .queue(Hide)?;
+        self.encoder.cursor = None;
+        self.write_output()
+    }
+
+    fn exit(&mut self) -> io::Result<()> {
+        self.inner.exit()?;
+        self.encoder
+            .writer
+            .queue(ResetColor)?
+            .queue(LeaveAlternateScreen)?
+            .queue(Show)?;
+        self.encoder.cursor = None;
+        self.write_output()
+    }
+
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.inner.move_to(x, y)?;
+        self.encoder.move_to(x, y)
+    }
+
+    fn set_color(&mut self, color: Option<Color>) -> io::Result<()> {
+        self.inner.set_color(color)?;
+        self.encoder.set_color(color)
+    }
+
+    fn set_background(&mut self, color: Option<Color>) -> io::Result<()> {
+        self.inner.set_background(color)?;
+        self.encoder.set_background(color)
+    }
+
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        self.inner.write(text)?;
+        self.encoder.write(text)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.inner.clear()?;
+        self.encoder.clear()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        self.write_output()
+    }
+
+    fn size(&self) -> io::Result<(u16, u16)> {
+        self.inner.size()
+    }
+
+    fn poll_event(&mut self, timeout: Duration) -> io::Result<Option<CrosstermEvent>> {
+        let event = self.inner.poll_event(timeout)?;
+        if let Some(CrosstermEvent::Resize(width, height)) = event {
+            self.write_event("r", &format!("{width}x{height}"))?;
+        }
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crossterm_exit_pops_keyboard_flags() {
+        // Another backend entered the terminal and pushed the flags
+        TERMINAL_ENTERED.store(true, Ordering::SeqCst);
+        KEYBOARD_ENHANCED.store(true, Ordering::SeqCst);
+        let mut backend = CrosstermBackend::new(vec![]);
+        backend.exit().unwrap();
+        assert!(backend.writer().starts_with(b"\x1b[<1u"));
+        assert!(!KEYBOARD_ENHANCED.load(Ordering::SeqCst));
+        assert!(!TERMINAL_ENTERED.load(Ordering::SeqCst));
+
+        // Exiting a terminal that was already restored does nothing
+        let mut backend = CrosstermBackend::new(vec![]);
+        backend.exit().unwrap();
+        assert_eq!(backend.writer(), b"");
+    }
+
+    #[test]
+    fn crossterm_truecolor() {
+        let mut backend = CrosstermBackend::new(vec![]).with_truecolor(true);
+        backend.set_color(Some(Color::rgb(1, 2, 3))).unwrap();
+        assert_eq!(backend.writer(), b"\x1b[38;2;1;2;3m");
+
+        let mut backend = CrosstermBackend::new(vec![]).with_truecolor(false);
+        backend.set_color(Some(Color::rgb(255, 255, 255))).unwrap();
+        backend.set_background(None).unwrap();
+        assert_eq!(backend.writer(), b"\x1b[38;5;231m\x1b[49m");
+    }
+
+    #[test]
+    fn crossterm_cursor_movement() {
+        let mut backend = CrosstermBackend::new(vec![]);
+        backend.move_to(3, 4).unwrap();
+        backend.write("ab").unwrap();
+        backend.move_to(5, 4).unwrap();
+        backend.move_to(5, 6).unwrap();
+        backend.move_to(1, 6).unwrap();
+        assert_eq!(backend.writer(), b"\x1b[5;4Hab\x1b[7d\x1b[2G");
+    }
+
+    #[test]
+    fn memory_wide_chars() {
+        let mut backend = MemoryBackend::new(4, 1);
+        backend.write("字字").unwrap();
+        assert_eq!(backend.contents(), "字字\n");
+        backend.move_to(1, 0).unwrap();
+        backend.write("a").unwrap();
+        assert_eq!(backend.contents(), " a字\n");
+        assert_eq!(backend.char_at(3, 0), None);
+    }
+
+    #[test]
+    fn session_exits() {
+        let mut backend = MemoryBackend::new(1, 1);
+        let session = Session::enter(&mut backend).unwrap();
+        assert!(session.is_entered());
+        session.exit().unwrap();
+        assert!(!backend.is_entered());
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            let _session = Session::enter(&mut backend).unwrap();
+            panic!("exiting on unwind");
+        }));
+        assert!(result.is_err());
+        assert!(!backend.is_entered());
+    }
+
+    #[test]
+    fn record_loop() {
+        use crate::{frame::LoopConfig, screen::Screen};
+        use std::ops::ControlFlow;
+
+        let mut recorder = Recorder::headless(2, 1, vec![]).unwrap();
+        recorder.inner_mut().resize(1, 1);
+        let mut screen = Screen::new_cells(2, 1);
+        screen
+            .run_loop_with(&mut recorder, LoopConfig::new(1000.), |s, frame| {
+                s.draw_str("ab", 0, 0, Some(Color::rgb(1, 2, 3)));
+                Ok(match frame.number {
+                    0 => ControlFlow::Continue(()),
+                    _ => ControlFlow::Break(()),
+                })
+            })
+            .unwrap();
+        let (backend, cast) = recorder.into_inner();
+        assert!(!backend.is_entered());
+
+        let cast = String::from_utf8(cast).unwrap();
+        let lines: Vec<_> = cast.lines().collect();
+        assert!(lines[0].starts_with(r#"{"version": 2, "width": 2, "height": 1, "timestamp": "#));
+        let events: Vec<_> = lines[1..]
+            .iter()
+            .map(|line| line.split_once(", ").unwrap().1)
+            .collect();
+        assert_eq!(
+            events,
+            [
+                r#""o", "\u001b[?1049h\u001b[?25l"]"#,
+                r#""r", "1x1"]"#,
+                r#""o", "\u001b[2J\u001b[1;1H\u001b[38;2;1;2;3m\u001b[49mab"]"#,
+                r#""o", "\u001b[0m\u001b[?1049l\u001b[?25h"]"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn json_strings() {
+        assert_eq!(json_string("a\"\\\n\u{1b}⠁"), r#""a\"\\\n\u001b⠁""#);
+    }
+}
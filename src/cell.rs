@@ -73,6 +73,21 @@ pub enum OffsetCell {
     },
 }
 
+impl OffsetCell {
+    /// Returns the cells occupied by this offset cell, in the order
+    /// `[upper left, upper right, lower left, lower right]`.
+    ///
+    /// Positions that are not occupied due to the alignment are `None`.
+    pub const fn parts(self) -> [Option<Cell>; 4] {
+        match self {
+            OffsetCell::Aligned { cell } => [Some(cell), None, None, None],
+            OffsetCell::Horizontal { left, right } => [Some(left), Some(right), None, None],
+            OffsetCell::Vertical { up, down } => [Some(up), None, Some(down), None],
+            OffsetCell::Corner { ul, ur, dl, dr } => [Some(ul), Some(ur), Some(dl), Some(dr)],
+        }
+    }
+}
+
 /// A cell is exactly 2 pixels wide, since it consists of one braille character.
 pub const PIXEL_WIDTH: u8 = 2;
 /// A cell is exactly 4 pixels tall, since it consists of one braille character.
@@ -2,14 +2,32 @@
 //!
 //! This uses [`crossterm::style::Color`] to represent ANSI terminal colors.
 
-use std::cmp::Ordering;
+use std::{cmp::Ordering, env};
 
 use crossterm::style;
 
 use crate::cell::Cell;
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Color(pub u8);
+/// A terminal color. This is either an 8-bit ANSI color, which is supported by most terminals,
+/// or an exact 24-bit RGB ("truecolor") value.
+///
+/// Truecolor values can be converted into the closest ANSI color using [`Color::to_ansi()`]
+/// when the terminal doesn't support them.
+///
+/// Before version 2.0, this was a `Color(u8)` tuple struct. ANSI colors are now [`Color::Ansi`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Color {
+    /// An 8-bit ANSI color value.
+    Ansi(u8),
+    /// An exact 24-bit RGB color.
+    Rgb(u8, u8, u8),
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Self::Ansi(0)
+    }
+}
 
 // RGB, GREYSCALE: These are the values most terminals seem to use
 // RGB must begin with 0 and end with 255
@@ -105,7 +123,41 @@ use standard::*;
 impl Color {
     /// Creates a new color from an 8-bit ANSI color value.
     pub const fn new(color: u8) -> Self {
-        Self(color)
+        Self::Ansi(color)
+    }
+    /// Creates a new exact 24-bit RGB color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::color::Color;
+    ///
+    /// let color = Color::rgb(12, 34, 56);
+    /// assert_eq!(color.to_rgb_approximate(), (12, 34, 56));
+    /// ```
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::Rgb(r, g, b)
+    }
+    /// Returns whether this is an exact 24-bit RGB color.
+    pub const fn is_rgb(self) -> bool {
+        matches!(self, Self::Rgb(..))
+    }
+    /// Returns the ANSI color closest to this color. ANSI colors are returned unchanged, and
+    /// RGB colors are approximated using [`Color::from_rgb_approximate()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::color::Color;
+    ///
+    /// assert_eq!(Color::rgb(95, 135, 215).to_ansi(), Color::from_ansi_components(1, 2, 4));
+    /// assert_eq!(Color::new(3).to_ansi(), Color::new(3));
+    /// ```
+    pub fn to_ansi(self) -> Self {
+        match self {
+            Self::Ansi(_) => self,
+            Self::Rgb(r, g, b) => Self::from_rgb_approximate(r, g, b),
+        }
     }
     /// Returns an ANSI color that is visually similar to the specified
     /// RGB value. This will not always be accurate, because there are only
@@ -183,7 +235,7 @@ impl Color {
     /// Each component may span from 0 to 5 (inclusive). If any values are higher, they
     /// are clipped to the maximum value (5).
     pub fn from_ansi_components(r: u8, g: u8, b: u8) -> Self {
-        Self(r.min(5) * 36 + g.min(5) * 6 + b.min(5) + 16)
+        Self::Ansi(r.min(5) * 36 + g.min(5) * 6 + b.min(5) + 16)
     }
     /// Returns a new color with the specified greyscale value. The value may be
     /// between 0 and 23 (inclusive), and represents a scale from black to white.
@@ -192,15 +244,20 @@ impl Color {
     /// Note that most terminals will not represent 0 with black and 23 with white;
     /// consider using `from_ansi_rgb(0, 0, 0)` and `from_ansi_rgb(5, 5, 5)` instead.
     pub fn from_ansi_greyscale(step: u8) -> Self {
-        Self(232 + step.min(23))
+        Self::Ansi(232 + step.min(23))
     }
-    /// Returns the approximate RGB color associated with this ANSI color.
+    /// Returns the approximate RGB color associated with this color.
     ///
-    /// This is not always accurate; terminals may always choose to theme
+    /// For ANSI colors, this is not always accurate; terminals may always choose to theme
     /// ANSI colors differently. In particular, the standard and high-intensity
     /// ANSI colors (color values from 0 to 15) are often altered by custom themes.
+    /// RGB colors are returned exactly.
     pub const fn to_rgb_approximate(self) -> (u8, u8, u8) {
-        match self.0 {
+        let ansi = match self {
+            Self::Ansi(ansi) => ansi,
+            Self::Rgb(r, g, b) => return (r, g, b),
+        };
+        match ansi {
             // The standard colors are simple approximations, because every terminal does it differently.
             // This is a particularly simple choice of colors, following the windows XP console.
             0 => (0, 0, 0),
@@ -221,7 +278,7 @@ impl Color {
             15 => (255, 255, 255),
             // 3-component (RGB) colors
             16..=231 => {
-                let offset = self.0 - 16;
+                let offset = ansi - 16;
                 let r = (offset / 36) % 6;
                 let g = (offset / 6) % 6;
                 let b = offset % 6;
@@ -229,7 +286,7 @@ impl Color {
             }
             // Greyscale colors
             232..=255 => {
-                let step = self.0 - 232;
+                let step = ansi - 232;
                 (
                     GREYSCALE[step as usize],
                     GREYSCALE[step as usize],
@@ -241,10 +298,19 @@ impl Color {
 
     /// Returns the equivalent crossterm color, for the purposes of integration
     pub const fn to_crossterm_color(self) -> style::Color {
-        style::Color::AnsiValue(self.0)
+        match self {
+            Self::Ansi(ansi) => style::Color::AnsiValue(ansi),
+            Self::Rgb(r, g, b) => style::Color::Rgb { r, g, b },
+        }
     }
 }
 
+/// Returns whether the terminal advertises support for 24-bit colors,
+/// using the `COLORTERM` environment variable.
+pub fn truecolor_supported() -> bool {
+    env::var("COLORTERM").is_ok_and(|v| v == "truecolor" || v == "24bit")
+}
+
 pub struct ColorFlags {
     /// When `true`, color is applied when the cell is drawn, even if the cell is empty.
     ///
@@ -256,13 +322,25 @@ pub struct ColorFlags {
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub struct ColoredCell {
     pub cell: Cell,
+    /// The color of the set pixels in the cell.
     pub color: Option<Color>,
+    /// The color behind the cell, visible through its unset pixels.
+    pub background: Option<Color>,
 }
 
 impl ColoredCell {
-    /// Creates a new [`ColoredCell`] from parameters
+    /// Creates a new [`ColoredCell`] from parameters, without a background color.
     pub const fn new(cell: Cell, color: Option<Color>) -> Self {
-        Self { cell, color }
+        Self {
+            cell,
+            color,
+            background: None,
+        }
+    }
+
+    /// Returns a copy of this cell with the given background color.
+    pub const fn with_background(self, background: Option<Color>) -> Self {
+        Self { background, ..self }
     }
 
     /// Combines this cell's pixel data with the argument [`Cell`] with a bitwise OR.
@@ -272,6 +350,13 @@ impl ColoredCell {
             self.color = color;
         }
     }
+
+    /// Sets this cell's background color, if one is given.
+    pub fn merge_background(&mut self, background: Option<Color>) {
+        if background.is_some() {
+            self.background = background;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -351,10 +436,37 @@ mod tests {
     fn test_greyscale_incrementing() {
         let colors: Vec<_> = (0..24).map(Color::from_ansi_greyscale).collect();
         let mut sorted = colors.clone();
-        sorted.sort_by(|a, b| a.to_rgb_approximate().0.cmp(&b.to_rgb_approximate().0));
+        sorted.sort_by_key(|a| a.to_rgb_approximate().0);
         assert_eq!(colors, sorted)
     }
 
+    #[test]
+    fn test_rgb_to_ansi() {
+        assert_eq!(
+            Color::rgb(0, 0, 0).to_ansi(),
+            Color::from_ansi_components(0, 0, 0)
+        );
+        assert_eq!(
+            Color::rgb(58, 58, 58).to_ansi(),
+            Color::from_ansi_greyscale(5)
+        );
+        assert_eq!(Color::rgb(1, 2, 3).to_rgb_approximate(), (1, 2, 3));
+        assert!(Color::rgb(1, 2, 3).is_rgb());
+        assert!(!Color::rgb(1, 2, 3).to_ansi().is_rgb());
+    }
+
+    #[test]
+    fn test_crossterm_color() {
+        assert_eq!(
+            Color::new(42).to_crossterm_color(),
+            style::Color::AnsiValue(42)
+        );
+        assert_eq!(
+            Color::rgb(1, 2, 3).to_crossterm_color(),
+            style::Color::Rgb { r: 1, g: 2, b: 3 }
+        );
+    }
+
     #[test]
     fn test_standard_color_approx() {
         assert_eq!(Color::standard_color_approximate(12, 8, 3), standard::BLACK);
@@ -1,10 +1,24 @@
-//! Key event handling.
+//! Input event handling.
 
-use crossterm::event::{self, KeyCode};
+use crossterm::event::{self, KeyCode, KeyEventKind, KeyModifiers, MouseEventKind};
 
-/// A keyboard event. Includes most keys on most keyboards, but does not include all keys.
+use crate::cell::{PIXEL_HEIGHT, PIXEL_WIDTH};
+
+/// An input event.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Event {
+    /// A key was pressed, repeated or released.
+    Key(KeyEvent),
+    /// The mouse was clicked, dragged, scrolled or moved. Only reported when mouse capture
+    /// is enabled, see [`crate::backend::CrosstermBackend::with_mouse_capture()`].
+    Mouse(MouseEvent),
+    /// The terminal was resized to the given size, in cells.
+    Resize { width: u16, height: u16 },
+}
+
+/// A key on the keyboard. Includes most keys on most keyboards, but does not include all keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Key {
     Right,
     Left,
     Up,
@@ -14,10 +28,94 @@ pub enum Event {
     Esc,
     Backspace,
     Tab,
+    /// Shift+Tab.
+    BackTab,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Delete,
+    Insert,
+    /// A function key, e.g. `F(1)` for F1.
+    F(u8),
+}
+
+/// The modifier keys held down during a key event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    /// The "super" key, also known as the Windows or Command key.
+    pub super_key: bool,
+}
+
+/// Whether a key was pressed, held down or released.
+///
+/// Terminals only report [`KeyKind::Repeat`] and [`KeyKind::Release`] if they support
+/// the kitty keyboard protocol; elsewhere, every key event is a [`KeyKind::Press`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KeyKind {
+    #[default]
+    Press,
+    /// The key was held down long enough to repeat.
+    Repeat,
+    Release,
+}
+
+/// A keyboard event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub modifiers: Modifiers,
+    pub kind: KeyKind,
+}
+
+/// A mouse button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// The kind of a mouse event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MouseKind {
+    /// A button was pressed.
+    Down(MouseButton),
+    /// A button was released.
+    Up(MouseButton),
+    /// The mouse was moved while a button was held down.
+    Drag(MouseButton),
+    /// The mouse was moved without any buttons held down.
+    Moved,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+}
+
+/// A mouse event.
+///
+/// The terminal only reports the cell that the mouse is over. The pixel coordinates
+/// are an estimate, pointing to the pixel near the center of that cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MouseEvent {
+    pub kind: MouseKind,
+    /// The cell column of the mouse, counting from the left edge of the terminal.
+    pub column: u16,
+    /// The cell row of the mouse, counting from the top edge of the terminal.
+    pub row: u16,
+    /// The estimated pixel x coordinate of the mouse on the screen.
+    pub x: i32,
+    /// The estimated pixel y coordinate of the mouse on the screen.
+    pub y: i32,
+    pub modifiers: Modifiers,
 }
 
 /// A direction. This is a convenience enum to abstract some of the directionality handling away.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Direction {
     Up,
     Left,
@@ -25,25 +123,196 @@ pub enum Direction {
     Right,
 }
 
+impl Modifiers {
+    /// No modifier keys.
+    pub const NONE: Self = Self {
+        shift: false,
+        control: false,
+        alt: false,
+        super_key: false,
+    };
+
+    /// Returns true when no modifier keys are held.
+    pub const fn is_empty(self) -> bool {
+        !(self.shift || self.control || self.alt || self.super_key)
+    }
+
+    /// Converts from crossterm's modifier flags.
+    pub const fn from_crossterm(modifiers: KeyModifiers) -> Self {
+        Self {
+            shift: modifiers.contains(KeyModifiers::SHIFT),
+            control: modifiers.contains(KeyModifiers::CONTROL),
+            alt: modifiers.contains(KeyModifiers::ALT),
+            super_key: modifiers.contains(KeyModifiers::SUPER),
+        }
+    }
+}
+
+impl KeyEvent {
+    /// Creates a new key press event without modifiers.
+    pub const fn new(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: Modifiers::NONE,
+            kind: KeyKind::Press,
+        }
+    }
+
+    /// Returns true if the key was pressed or repeated, i.e. not released.
+    pub const fn is_press(&self) -> bool {
+        !matches!(self.kind, KeyKind::Release)
+    }
+}
+
+impl Direction {
+    /// Returns the direction as an `(x, y)` unit vector in screen coordinates,
+    /// i.e. with y increasing downwards.
+    ///
+    /// See [`crate::input::InputState::direction_vector()`] for combining the directions of
+    /// multiple held keys into diagonals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::event::Direction;
+    ///
+    /// assert_eq!(Direction::Up.vector(), (0, -1));
+    /// assert_eq!(Direction::Right.vector(), (1, 0));
+    /// ```
+    pub const fn vector(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Down => (0, 1),
+            Direction::Right => (1, 0),
+        }
+    }
+}
+
+impl MouseButton {
+    /// Converts from a crossterm mouse button.
+    pub const fn from_crossterm(button: event::MouseButton) -> Self {
+        match button {
+            event::MouseButton::Left => MouseButton::Left,
+            event::MouseButton::Right => MouseButton::Right,
+            event::MouseButton::Middle => MouseButton::Middle,
+        }
+    }
+}
+
+impl MouseEvent {
+    /// Creates a new mouse event at the given cell position without modifiers,
+    /// estimating its pixel position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::event::{MouseEvent, MouseKind};
+    ///
+    /// let event = MouseEvent::new(MouseKind::Moved, 3, 2);
+    /// assert_eq!((event.x, event.y), (7, 10));
+    /// ```
+    pub const fn new(kind: MouseKind, column: u16, row: u16) -> Self {
+        Self {
+            kind,
+            column,
+            row,
+            x: column as i32 * PIXEL_WIDTH as i32 + PIXEL_WIDTH as i32 / 2,
+            y: row as i32 * PIXEL_HEIGHT as i32 + PIXEL_HEIGHT as i32 / 2,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    /// Create a mouse event from a crossterm mouse event.
+    pub const fn from_crossterm(event: event::MouseEvent) -> Self {
+        let kind = match event.kind {
+            MouseEventKind::Down(b) => MouseKind::Down(MouseButton::from_crossterm(b)),
+            MouseEventKind::Up(b) => MouseKind::Up(MouseButton::from_crossterm(b)),
+            MouseEventKind::Drag(b) => MouseKind::Drag(MouseButton::from_crossterm(b)),
+            MouseEventKind::Moved => MouseKind::Moved,
+            MouseEventKind::ScrollUp => MouseKind::ScrollUp,
+            MouseEventKind::ScrollDown => MouseKind::ScrollDown,
+            MouseEventKind::ScrollLeft => MouseKind::ScrollLeft,
+            MouseEventKind::ScrollRight => MouseKind::ScrollRight,
+        };
+        Self {
+            modifiers: Modifiers::from_crossterm(event.modifiers),
+            ..Self::new(kind, event.column, event.row)
+        }
+    }
+}
+
+impl Key {
+    /// Returns the direction associated with this key, if any.
+    ///
+    /// Includes arrow keys, and a configurable keyset for Up, Left, Down, Right.
+    pub fn direction(self, up: char, left: char, down: char, right: char) -> Option<Direction> {
+        match self {
+            Key::Up => Some(Direction::Up),
+            Key::Left => Some(Direction::Left),
+            Key::Down => Some(Direction::Down),
+            Key::Right => Some(Direction::Right),
+            Key::Char(c) if c == up => Some(Direction::Up),
+            Key::Char(c) if c == left => Some(Direction::Left),
+            Key::Char(c) if c == down => Some(Direction::Down),
+            Key::Char(c) if c == right => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    /// Create a key from a crossterm key code, if possible.
+    pub fn from_crossterm_key_code(code: KeyCode) -> Option<Self> {
+        match code {
+            KeyCode::Backspace => Some(Key::Backspace),
+            KeyCode::Enter => Some(Key::Enter),
+            KeyCode::Left => Some(Key::Left),
+            KeyCode::Right => Some(Key::Right),
+            KeyCode::Up => Some(Key::Up),
+            KeyCode::Down => Some(Key::Down),
+            KeyCode::Home => Some(Key::Home),
+            KeyCode::End => Some(Key::End),
+            KeyCode::PageUp => Some(Key::PageUp),
+            KeyCode::PageDown => Some(Key::PageDown),
+            KeyCode::Tab => Some(Key::Tab),
+            KeyCode::BackTab => Some(Key::BackTab),
+            KeyCode::Delete => Some(Key::Delete),
+            KeyCode::Insert => Some(Key::Insert),
+            KeyCode::F(n) => Some(Key::F(n)),
+            KeyCode::Char(c) => Some(Key::Char(c)),
+            KeyCode::Esc => Some(Key::Esc),
+            _ => None,
+        }
+    }
+}
+
 impl Event {
+    /// Returns the key that was pressed or repeated in this event, if any.
+    /// Key releases are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::event::{Event, Key, KeyEvent};
+    ///
+    /// let event = Event::Key(KeyEvent::new(Key::Esc));
+    /// assert_eq!(event.pressed(), Some(Key::Esc));
+    /// ```
+    pub fn pressed(&self) -> Option<Key> {
+        match self {
+            Event::Key(key) if key.is_press() => Some(key.key),
+            _ => None,
+        }
+    }
     /// Returns the direction associated with this event, if any.
     ///
     /// Includes arrow keys, and a configurable keyset for Up, Left, Down, Right.
+    /// Only key presses and repeats have a direction, not key releases.
     ///
     /// A special case of this (using WASD for the directions) is common enough that it has
     /// a special method: [`Event::direction_wasd()`].
     pub fn direction(&self, up: char, left: char, down: char, right: char) -> Option<Direction> {
-        match self {
-            Event::Up => Some(Direction::Up),
-            Event::Left => Some(Direction::Left),
-            Event::Down => Some(Direction::Down),
-            Event::Right => Some(Direction::Right),
-            Event::Char(c) if *c == up => Some(Direction::Up),
-            Event::Char(c) if *c == left => Some(Direction::Left),
-            Event::Char(c) if *c == down => Some(Direction::Down),
-            Event::Char(c) if *c == right => Some(Direction::Right),
-            _ => None,
-        }
+        self.pressed()
+            .and_then(|key| key.direction(up, left, down, right))
     }
     /// Returns the direction associated with this event, if any.
     ///
@@ -54,19 +323,96 @@ impl Event {
     /// Create an event from a crossterm event, if possible.
     pub fn from_crossterm_event(event: event::Event) -> Option<Self> {
         match event {
-            event::Event::Key(key) => match key.code {
-                KeyCode::Backspace => Some(Event::Backspace),
-                KeyCode::Enter => Some(Event::Enter),
-                KeyCode::Left => Some(Event::Left),
-                KeyCode::Right => Some(Event::Right),
-                KeyCode::Up => Some(Event::Up),
-                KeyCode::Down => Some(Event::Down),
-                KeyCode::Tab => Some(Event::Tab),
-                KeyCode::Char(c) => Some(Event::Char(c)),
-                KeyCode::Esc => Some(Event::Esc),
-                _ => None,
-            },
+            event::Event::Key(key) => Key::from_crossterm_key_code(key.code).map(|code| {
+                Event::Key(KeyEvent {
+                    key: code,
+                    modifiers: Modifiers::from_crossterm(key.modifiers),
+                    kind: match key.kind {
+                        KeyEventKind::Press => KeyKind::Press,
+                        KeyEventKind::Repeat => KeyKind::Repeat,
+                        KeyEventKind::Release => KeyKind::Release,
+                    },
+                })
+            }),
+            event::Event::Mouse(mouse) => Some(Event::Mouse(MouseEvent::from_crossterm(mouse))),
+            event::Event::Resize(width, height) => Some(Event::Resize { width, height }),
             _ => None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyEvent as CrosstermKeyEvent, KeyEventState};
+
+    use super::*;
+
+    #[test]
+    fn key_event_conversion() {
+        let event = event::Event::Key(CrosstermKeyEvent::new_with_kind(
+            KeyCode::F(5),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+            KeyEventKind::Release,
+        ));
+        assert_eq!(
+            Event::from_crossterm_event(event),
+            Some(Event::Key(KeyEvent {
+                key: Key::F(5),
+                modifiers: Modifiers {
+                    shift: true,
+                    control: true,
+                    ..Modifiers::NONE
+                },
+                kind: KeyKind::Release,
+            }))
+        );
+        let event = event::Event::Key(CrosstermKeyEvent {
+            code: KeyCode::CapsLock,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        });
+        assert_eq!(Event::from_crossterm_event(event), None);
+    }
+
+    #[test]
+    fn mouse_event_conversion() {
+        let event = event::Event::Mouse(event::MouseEvent {
+            kind: MouseEventKind::Drag(event::MouseButton::Right),
+            column: 0,
+            row: 1,
+            modifiers: KeyModifiers::ALT,
+        });
+        assert_eq!(
+            Event::from_crossterm_event(event),
+            Some(Event::Mouse(MouseEvent {
+                kind: MouseKind::Drag(MouseButton::Right),
+                column: 0,
+                row: 1,
+                x: 1,
+                y: 6,
+                modifiers: Modifiers {
+                    alt: true,
+                    ..Modifiers::NONE
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn directions() {
+        let press = |key| Event::Key(KeyEvent::new(key));
+        assert_eq!(press(Key::Up).direction_wasd(), Some(Direction::Up));
+        assert_eq!(
+            press(Key::Char('d')).direction_wasd(),
+            Some(Direction::Right)
+        );
+        assert_eq!(press(Key::Char('x')).direction_wasd(), None);
+        assert_eq!(press(Key::PageUp).direction_wasd(), None);
+        let release = Event::Key(KeyEvent {
+            kind: KeyKind::Release,
+            ..KeyEvent::new(Key::Left)
+        });
+        assert_eq!(release.direction_wasd(), None);
+    }
+}
@@ -0,0 +1,113 @@
+//! Module for exporting screens as HTML.
+
+use super::*;
+
+use crate::screen::{Screen, TextCell};
+
+/// Appends a character to HTML text, escaping it if needed.
+fn push_escaped(html: &mut String, c: char) {
+    match c {
+        '&' => html.push_str("&amp;"),
+        '<' => html.push_str("&lt;"),
+        '>' => html.push_str("&gt;"),
+        c => html.push(c),
+    }
+}
+
+/// Returns the inline CSS for a span with the given colors, or `None` if no span is needed.
+fn span_style(color: Option<Color>, background: Option<Color>) -> Option<String> {
+    let mut declarations = vec![];
+    if let Some(color) = color {
+        declarations.push(format!("color: {}", css_color(color)));
+    }
+    if let Some(background) = background {
+        declarations.push(format!("background-color: {}", css_color(background)));
+    }
+    (!declarations.is_empty()).then(|| declarations.join("; "))
+}
+
+impl Screen {
+    /// Serializes the screen to an HTML fragment, for sharing output in web pages.
+    ///
+    /// The fragment is a `<pre class="ti">` element with one line per row, like
+    /// [`Screen::rasterize()`]. Runs of cells with the same colors are wrapped in a `<span>`
+    /// styled with the current cell colors and backgrounds, while uncolored cells are left as-is
+    /// so they take on the page's colors. ANSI colors are approximated with
+    /// [`Color::to_rgb_approximate()`]. Text drawn with [`Screen::draw_char()`] is included.
+    ///
+    /// The page should use a font with braille characters, such as `MonoBrailleCC0.otf`
+    /// from this repository, for the output to line up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::color::Color;
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// let mut screen = Screen::new_cells(3, 1);
+    /// screen.draw_pixel_colored(0, 0, Blit::Set, Some(Color::rgb(255, 0, 0)));
+    /// screen.draw_char('<', 2, 0, None);
+    /// assert_eq!(
+    ///     screen.to_html(),
+    ///     "<pre class=\"ti\"><span style=\"color: #ff0000\">⠁</span>⠀&lt;\n</pre>",
+    /// );
+    /// ```
+    pub fn to_html(&self) -> String {
+        let mut html = String::from("<pre class=\"ti\">");
+        for y in 0..self.height() {
+            let mut open: Option<Option<String>> = None;
+            for x in 0..self.width() {
+                let (c, color) = match self.get_text(x, y) {
+                    Some(TextCell::Char { c, color }) => (c, color),
+                    Some(TextCell::Continuation) => continue,
+                    None => (
+                        self.get_cell(x, y).unwrap().to_braille_char(),
+                        self.get_color(x, y),
+                    ),
+                };
+                let style = span_style(color, self.get_background(x, y));
+                if open.as_ref() != Some(&style) {
+                    if let Some(Some(_)) = open {
+                        html.push_str("</span>");
+                    }
+                    if let Some(style) = &style {
+                        html.push_str(&format!("<span style=\"{style}\">"));
+                    }
+                    open = Some(style);
+                }
+                push_escaped(&mut html, c);
+            }
+            if let Some(Some(_)) = open {
+                html.push_str("</span>");
+            }
+            html.push('\n');
+        }
+        html.push_str("</pre>");
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::screen::Blit;
+
+    use super::*;
+
+    #[test]
+    fn html_spans() {
+        let mut screen = Screen::new_cells(4, 2);
+        let red = Some(Color::rgb(255, 0, 0));
+        screen.draw_pixel_colored(0, 0, Blit::Set, red);
+        screen.draw_pixel_colored(2, 0, Blit::Set, red);
+        screen.draw_cell_background(Color::new(4), 1, 0, 0);
+        screen.draw_str("字&", 0, 1, red);
+        assert_eq!(
+            screen.to_html(),
+            "<pre class=\"ti\">\
+             <span style=\"color: #ff0000\">⠁</span>\
+             <span style=\"color: #ff0000; background-color: #000080\">⠁</span>⠀⠀\n\
+             <span style=\"color: #ff0000\">字&amp;</span>⠀\n\
+             </pre>"
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Module for exporting screens and sprites as images.
+
+use super::*;
+
+use std::path::Path;
+
+use image::{ImageFormat, ImageResult, Rgba, RgbaImage};
+
+use crate::{
+    cell::{Cell, PIXEL_HEIGHT, PIXEL_WIDTH},
+    color::ColoredCell,
+    screen::Screen,
+    sprite::Sprite,
+    units::index,
+};
+
+/// Converts a color to an opaque image pixel.
+fn rgba(color: Color) -> Rgba<u8> {
+    let (r, g, b) = color.to_rgb_approximate();
+    Rgba([r, g, b, 255])
+}
+
+/// Draws a grid of `width` by `height` cells, fetching each cell with `cell_at`.
+fn cells_to_image<F: Fn(u16, u16) -> ColoredCell>(
+    width: u16,
+    height: u16,
+    style: &ExportStyle,
+    cell_at: F,
+) -> RgbaImage {
+    let pitch = style.pitch();
+    let (cell_width, cell_height) = (PIXEL_WIDTH as u32 * pitch, PIXEL_HEIGHT as u32 * pitch);
+    let mut image = RgbaImage::new(width as u32 * cell_width, height as u32 * cell_height);
+    // Dots are centered in their share of the spacing
+    let margin = style.spacing / 2;
+    let radius = style.dot_size as f64 / 2.;
+    let in_dot = |dx: u32, dy: u32| {
+        let (Some(dx), Some(dy)) = (dx.checked_sub(margin), dy.checked_sub(margin)) else {
+            return false;
+        };
+        match style.shape {
+            DotShape::Square => dx < style.dot_size && dy < style.dot_size,
+            DotShape::Circle => {
+                // Sample at the center of each image pixel
+                let (dx, dy) = (dx as f64 + 0.5 - radius, dy as f64 + 0.5 - radius);
+                dx * dx + dy * dy <= radius * radius
+            }
+        }
+    };
+    for y in 0..height {
+        for x in 0..width {
+            let ColoredCell {
+                cell,
+                color,
+                background,
+            } = cell_at(x, y);
+            let color = rgba(color.unwrap_or(style.foreground));
+            let background = background.or(style.background).map(rgba);
+            let (left, top) = (x as u32 * cell_width, y as u32 * cell_height);
+            for dy in 0..cell_height {
+                for dx in 0..cell_width {
+                    let bit =
+                        Cell::from_bit_position((dx / pitch) as u8, (dy / pitch) as u8).unwrap();
+                    let lit = cell.bits & bit.bits != 0 && in_dot(dx % pitch, dy % pitch);
+                    if lit {
+                        image.put_pixel(left + dx, top + dy, color);
+                    } else if let Some(background) = background {
+                        image.put_pixel(left + dx, top + dy, background);
+                    }
+                }
+            }
+        }
+    }
+    image
+}
+
+impl Screen {
+    /// Draws the screen's pixels into an image, using the cell colors and backgrounds
+    /// of the current frame.
+    ///
+    /// RGB colors are reproduced exactly, while ANSI colors are approximated with
+    /// [`Color::to_rgb_approximate()`]. Text drawn with [`Screen::draw_char()`] is not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::color::Color;
+    /// use ti::export::ExportStyle;
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// let mut screen = Screen::new_cells(1, 1);
+    /// screen.draw_pixel_colored(1, 2, Blit::Set, Some(Color::rgb(255, 0, 0)));
+    /// let image = screen.to_image(&ExportStyle::new(2, 1));
+    /// assert_eq!(image.dimensions(), (6, 12));
+    /// assert_eq!(image.get_pixel(3, 6).0, [255, 0, 0, 255]);
+    /// assert_eq!(image.get_pixel(5, 6).0, [0, 0, 0, 0]);
+    /// ```
+    pub fn to_image(&self, style: &ExportStyle) -> RgbaImage {
+        cells_to_image(self.width(), self.height(), style, |x, y| {
+            ColoredCell::new(self.get_cell(x, y).unwrap(), self.get_color(x, y))
+                .with_background(self.get_background(x, y))
+        })
+    }
+
+    /// Saves the screen as a PNG file. See [`Screen::to_image()`].
+    pub fn save_png<P: AsRef<Path>>(&self, path: P, style: &ExportStyle) -> ImageResult<()> {
+        self.to_image(style)
+            .save_with_format(path, ImageFormat::Png)
+    }
+}
+
+impl Sprite {
+    /// Draws the sprite's pixels into an image, at a zero pixel offset.
+    ///
+    /// RGB colors are reproduced exactly, while ANSI colors are approximated with
+    /// [`Color::to_rgb_approximate()`].
+    pub fn to_image(&self, style: &ExportStyle) -> RgbaImage {
+        let width = self.default_width();
+        cells_to_image(width, self.default_height(), style, |x, y| {
+            self.offsets[0][index(x, y, width)]
+        })
+    }
+
+    /// Saves the sprite as a PNG file. See [`Sprite::to_image()`].
+    pub fn save_png<P: AsRef<Path>>(&self, path: P, style: &ExportStyle) -> ImageResult<()> {
+        self.to_image(style)
+            .save_with_format(path, ImageFormat::Png)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::screen::Blit;
+
+    use super::*;
+
+    #[test]
+    fn screen_image() {
+        let mut screen = Screen::new_pixels(4, 4);
+        screen.draw_pixel(0, 0, Blit::Set);
+        screen.draw_pixel_colored(3, 3, Blit::Set, Some(Color::new(9)));
+        screen.draw_cell_background(Color::rgb(0, 0, 255), 1, 0, 0);
+        let image = screen.to_image(&ExportStyle::default().with_background(Some(Color::new(0))));
+        assert_eq!(image.dimensions(), (4, 4));
+        let pixels: Vec<_> = image.pixels().map(|pixel| pixel.0).collect();
+        let (black, white, red, blue) = (
+            [0, 0, 0, 255],
+            [255, 255, 255, 255],
+            [255, 0, 0, 255],
+            [0, 0, 255, 255],
+        );
+        #[rustfmt::skip]
+        assert_eq!(pixels, [
+            white, black, blue, blue,
+            black, black, blue, blue,
+            black, black, blue, blue,
+            black, black, blue, red,
+        ]);
+    }
+
+    #[test]
+    fn sprite_png() {
+        let sprite = Sprite::from_braille_string(&["⣿⠁"], Some(Color::rgb(1, 2, 3)), 0).unwrap();
+        let style = ExportStyle::new(2, 2);
+        let path = std::env::temp_dir().join(format!("ti-sprite-{}.png", std::process::id()));
+        sprite.save_png(&path, &style).unwrap();
+        let image = image::open(&path).unwrap().into_rgba8();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(image, sprite.to_image(&style));
+        assert_eq!(image.dimensions(), (16, 16));
+        assert_eq!(image.get_pixel(1, 1).0, [1, 2, 3, 255]);
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(13, 5).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(9, 1).0, [1, 2, 3, 255]);
+    }
+
+    #[test]
+    fn circle_dots() {
+        let sprite = Sprite::from_braille_string(&["⠁"], None, 0).unwrap();
+        let image = sprite.to_image(&ExportStyle::new(4, 0).with_shape(DotShape::Circle));
+        let lit: Vec<Vec<bool>> = (0..4)
+            .map(|y| (0..4).map(|x| image.get_pixel(x, y).0[3] != 0).collect())
+            .collect();
+        assert_eq!(
+            lit,
+            [
+                [false, true, true, false],
+                [true, true, true, true],
+                [true, true, true, true],
+                [false, true, true, false],
+            ]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Module for exporting [`crate::screen::Screen`]s and [`crate::sprite::Sprite`]s to other formats,
+//! e.g. for documentation images or golden-image tests.
+//!
+//! Screens can be serialized to HTML fragments and SVG images without a terminal. With the `images`
+//! feature, screens and sprites can also be converted to `image::RgbaImage`s and saved as PNG files.
+mod html;
+#[cfg(feature = "images")]
+mod images;
+mod svg;
+
+use crate::color::Color;
+
+/// The shape of the dots drawn for lit pixels when exporting to an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DotShape {
+    /// Each dot fills a square.
+    #[default]
+    Square,
+    /// Each dot is a circle fitting in the square, like the dots of braille characters.
+    Circle,
+}
+
+/// Settings for how pixels are drawn when exporting to an image.
+///
+/// Each braille pixel is drawn as a dot `dot_size` image pixels across, separated from
+/// its neighbours by `spacing` image pixels. The default draws each braille pixel as a single
+/// image pixel, which is convenient for comparing images in tests.
+///
+/// # Examples
+///
+/// ```
+/// use ti::color::Color;
+/// use ti::export::ExportStyle;
+///
+/// // Large dots with visible gaps, on a black background
+/// let style = ExportStyle::new(3, 1).with_background(Some(Color::rgb(0, 0, 0)));
+/// assert_eq!(style.pitch(), 4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportStyle {
+    /// The width and height of each dot, in image pixels.
+    pub dot_size: u32,
+    /// The gap between neighbouring dots, in image pixels.
+    pub spacing: u32,
+    /// The shape of each dot.
+    pub shape: DotShape,
+    /// The color of lit pixels in cells without a color.
+    pub foreground: Color,
+    /// The color behind the dots in cells without a background color. `None` is transparent.
+    pub background: Option<Color>,
+}
+
+impl ExportStyle {
+    /// Creates a style with the given dot size and spacing, drawing uncolored pixels as white squares
+    /// on a transparent background.
+    pub const fn new(dot_size: u32, spacing: u32) -> Self {
+        Self {
+            dot_size,
+            spacing,
+            shape: DotShape::Square,
+            foreground: Color::rgb(255, 255, 255),
+            background: None,
+        }
+    }
+
+    /// Returns a copy of the style drawing dots with the given shape.
+    pub const fn with_shape(self, shape: DotShape) -> Self {
+        Self { shape, ..self }
+    }
+
+    /// Returns a copy of the style drawing lit pixels without a color in `foreground`.
+    pub const fn with_foreground(self, foreground: Color) -> Self {
+        Self { foreground, ..self }
+    }
+
+    /// Returns a copy of the style drawing `background` behind cells without a background color.
+    pub const fn with_background(self, background: Option<Color>) -> Self {
+        Self { background, ..self }
+    }
+
+    /// Returns the distance between the starts of neighbouring dots, in image pixels.
+    pub const fn pitch(&self) -> u32 {
+        self.dot_size + self.spacing
+    }
+}
+
+impl Default for ExportStyle {
+    /// Draws each braille pixel as a single image pixel.
+    fn default() -> Self {
+        Self::new(1, 0)
+    }
+}
+
+/// Formats a color as a CSS hex color, approximating ANSI colors with [`Color::to_rgb_approximate()`].
+fn css_color(color: Color) -> String {
+    let (r, g, b) = color.to_rgb_approximate();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
@@ -0,0 +1,118 @@
+//! Module for exporting screens as SVG images.
+
+use std::fmt::Write;
+
+use super::*;
+
+use crate::{
+    cell::{Cell, PIXEL_HEIGHT, PIXEL_WIDTH},
+    screen::Screen,
+};
+
+impl Screen {
+    /// Serializes the screen's pixels to an SVG image, with one shape per lit pixel.
+    ///
+    /// Each pixel takes up a square of [`ExportStyle::pitch()`] units, and is drawn as a `<rect>`
+    /// or a `<circle>` depending on the style. Lit pixels and cell backgrounds use the current cell
+    /// colors, and ANSI colors are approximated with [`Color::to_rgb_approximate()`].
+    /// Text drawn with [`Screen::draw_char()`] is not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::export::{DotShape, ExportStyle};
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// let mut screen = Screen::new_cells(1, 1);
+    /// screen.draw_pixel(1, 0, Blit::Set);
+    /// let svg = screen.to_svg(&ExportStyle::new(2, 0).with_shape(DotShape::Circle));
+    /// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"4\" height=\"8\""));
+    /// assert!(svg.contains("<circle cx=\"3\" cy=\"1\" r=\"1\" fill=\"#ffffff\"/>"));
+    /// ```
+    pub fn to_svg(&self, style: &ExportStyle) -> String {
+        let pitch = style.pitch();
+        let (cell_width, cell_height) = (PIXEL_WIDTH as u32 * pitch, PIXEL_HEIGHT as u32 * pitch);
+        let (width, height) = (
+            self.width() as u32 * cell_width,
+            self.height() as u32 * cell_height,
+        );
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\">\n"
+        );
+        if let Some(background) = style.background {
+            let _ = writeln!(
+                svg,
+                "<rect width=\"{width}\" height=\"{height}\" fill=\"{}\"/>",
+                css_color(background)
+            );
+        }
+        // Dots are centered in their share of the spacing
+        let margin = style.spacing as f64 / 2.;
+        let radius = style.dot_size as f64 / 2.;
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let (left, top) = (x as u32 * cell_width, y as u32 * cell_height);
+                if let Some(background) = self.get_background(x, y) {
+                    let _ = writeln!(
+                        svg,
+                        "<rect x=\"{left}\" y=\"{top}\" width=\"{cell_width}\" \
+                         height=\"{cell_height}\" fill=\"{}\"/>",
+                        css_color(background)
+                    );
+                }
+                let cell = self.get_cell(x, y).unwrap();
+                let fill = css_color(self.get_color(x, y).unwrap_or(style.foreground));
+                for py in 0..PIXEL_HEIGHT {
+                    for px in 0..PIXEL_WIDTH {
+                        let bit = Cell::from_bit_position(px, py).unwrap();
+                        if cell.bits & bit.bits == 0 {
+                            continue;
+                        }
+                        let dot_x = (left + px as u32 * pitch) as f64 + margin;
+                        let dot_y = (top + py as u32 * pitch) as f64 + margin;
+                        let _ = match style.shape {
+                            DotShape::Square => writeln!(
+                                svg,
+                                "<rect x=\"{dot_x}\" y=\"{dot_y}\" width=\"{0}\" height=\"{0}\" \
+                                 fill=\"{fill}\"/>",
+                                style.dot_size
+                            ),
+                            DotShape::Circle => writeln!(
+                                svg,
+                                "<circle cx=\"{}\" cy=\"{}\" r=\"{radius}\" fill=\"{fill}\"/>",
+                                dot_x + radius,
+                                dot_y + radius
+                            ),
+                        };
+                    }
+                }
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::screen::Blit;
+
+    use super::*;
+
+    #[test]
+    fn svg_shapes() {
+        let mut screen = Screen::new_cells(2, 1);
+        screen.draw_pixel_colored(0, 3, Blit::Set, Some(Color::rgb(1, 2, 3)));
+        screen.draw_cell_background(Color::new(12), 1, 0, 0);
+        let svg = screen.to_svg(&ExportStyle::new(1, 1).with_background(Some(Color::new(0))));
+        assert_eq!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"8\" height=\"8\" viewBox=\"0 0 8 8\">\n\
+             <rect width=\"8\" height=\"8\" fill=\"#000000\"/>\n\
+             <rect x=\"0.5\" y=\"6.5\" width=\"1\" height=\"1\" fill=\"#010203\"/>\n\
+             <rect x=\"4\" y=\"0\" width=\"4\" height=\"8\" fill=\"#0000ff\"/>\n\
+             </svg>\n"
+        );
+    }
+}
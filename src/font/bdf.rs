@@ -0,0 +1,262 @@
+//! Loading fonts in the Glyph Bitmap Distribution Format (BDF).
+
+use std::{fs, io, path::Path, str::SplitWhitespace};
+
+use super::{invalid_data, Font, Glyph};
+
+/// Parses the next whitespace-separated value of a line.
+fn next_value<T: std::str::FromStr>(values: &mut SplitWhitespace, keyword: &str) -> io::Result<T> {
+    values
+        .next()
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| invalid_data(format!("invalid or missing value for {keyword}")))
+}
+
+/// The properties of a glyph, collected between `STARTCHAR` and `ENDCHAR`.
+#[derive(Debug, Default)]
+struct GlyphProperties {
+    encoding: Option<i64>,
+    advance: Option<i32>,
+    bbx: Option<(u16, u16, i32, i32)>,
+}
+
+impl Font {
+    /// Loads a font from the contents of a BDF file.
+    ///
+    /// Glyphs are mapped to the characters given by their encodings, which are assumed to be
+    /// Unicode code points (as in fonts with the `ISO10646-1` charset). Glyphs without an encoding
+    /// are skipped. If the font has a `DEFAULT_CHAR` property, that glyph is used for missing characters.
+    ///
+    /// The line height is the sum of the font's ascent and descent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if the file is not a valid BDF font.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::font::Font;
+    ///
+    /// let bdf = "\
+    /// STARTFONT 2.1
+    /// FONTBOUNDINGBOX 2 3 0 -1
+    /// CHARS 1
+    /// STARTCHAR period
+    /// ENCODING 46
+    /// DWIDTH 2 0
+    /// BBX 1 1 0 0
+    /// BITMAP
+    /// 80
+    /// ENDCHAR
+    /// ENDFONT
+    /// ";
+    /// let font = Font::from_bdf(bdf.as_bytes()).unwrap();
+    /// assert_eq!((font.height(), font.line_height()), (3, 3));
+    /// assert_eq!(font.glyph('.').unwrap().y_offset, 1);
+    /// ```
+    pub fn from_bdf(data: &[u8]) -> io::Result<Self> {
+        let text = std::str::from_utf8(data).map_err(invalid_data)?;
+        let mut lines = text.lines();
+        if !lines
+            .next()
+            .is_some_and(|line| line.starts_with("STARTFONT"))
+        {
+            return Err(invalid_data("missing STARTFONT"));
+        }
+
+        let mut bounding_box = None;
+        let (mut ascent, mut descent) = (None, None);
+        let mut default_advance = None;
+        let mut default_char = None;
+        let mut glyphs = vec![];
+        let mut glyph: Option<GlyphProperties> = None;
+
+        while let Some(line) = lines.next() {
+            let mut values = line.split_whitespace();
+            let Some(keyword) = values.next() else {
+                continue;
+            };
+            match (keyword, &mut glyph) {
+                ("FONTBOUNDINGBOX", None) => {
+                    bounding_box = Some((
+                        next_value::<u16>(&mut values, keyword)?,
+                        next_value::<u16>(&mut values, keyword)?,
+                        next_value::<i32>(&mut values, keyword)?,
+                        next_value::<i32>(&mut values, keyword)?,
+                    ));
+                }
+                ("FONT_ASCENT", None) => ascent = Some(next_value::<i32>(&mut values, keyword)?),
+                ("FONT_DESCENT", None) => descent = Some(next_value::<i32>(&mut values, keyword)?),
+                ("DEFAULT_CHAR", None) => {
+                    default_char = char::from_u32(next_value(&mut values, keyword)?);
+                }
+                ("DWIDTH", None) => default_advance = Some(next_value(&mut values, keyword)?),
+                ("STARTCHAR", None) => glyph = Some(GlyphProperties::default()),
+                ("ENCODING", Some(glyph)) => {
+                    glyph.encoding = Some(next_value(&mut values, keyword)?);
+                }
+                ("DWIDTH", Some(glyph)) => glyph.advance = Some(next_value(&mut values, keyword)?),
+                ("BBX", Some(glyph)) => {
+                    glyph.bbx = Some((
+                        next_value(&mut values, keyword)?,
+                        next_value(&mut values, keyword)?,
+                        next_value(&mut values, keyword)?,
+                        next_value(&mut values, keyword)?,
+                    ));
+                }
+                ("BITMAP", Some(properties)) => {
+                    let (width, height, x_offset, y_offset) = properties
+                        .bbx
+                        .or(bounding_box)
+                        .ok_or_else(|| invalid_data("missing BBX"))?;
+                    let row_bytes = (width as usize).div_ceil(8);
+                    let mut bitmap = vec![];
+                    for _ in 0..height {
+                        let row = lines
+                            .next()
+                            .ok_or_else(|| invalid_data("unexpected end of BITMAP"))?
+                            .trim();
+                        let bytes = (0..row_bytes)
+                            .map(|i| {
+                                row.get(i * 2..i * 2 + 2)
+                                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                                    .ok_or_else(|| invalid_data("invalid BITMAP row"))
+                            })
+                            .collect::<io::Result<Vec<u8>>>()?;
+                        bitmap.extend(
+                            (0..width as usize).map(|x| bytes[x / 8] & (0x80 >> (x % 8)) != 0),
+                        );
+                    }
+                    let advance = properties
+                        .advance
+                        .or(default_advance)
+                        .unwrap_or(width as i32);
+                    // Glyphs are positioned relative to the baseline, rather than the top of the line
+                    let y_offset = -(y_offset as i64 + height as i64);
+                    let glyph = Glyph::new(width, height, advance.max(0) as u16, bitmap).unwrap();
+                    glyphs.push((properties.encoding, glyph, x_offset, y_offset));
+                }
+                ("ENDCHAR", Some(_)) => glyph = None,
+                ("ENDFONT", None) => break,
+                _ => (),
+            }
+        }
+        if glyph.is_some() {
+            return Err(invalid_data("missing ENDCHAR"));
+        }
+
+        let ascent = ascent
+            .map(i64::from)
+            .or(bounding_box.map(|(_, height, _, y_offset)| height as i64 + y_offset as i64))
+            .ok_or_else(|| invalid_data("missing FONT_ASCENT"))?;
+        let descent = descent
+            .map(i64::from)
+            .or(bounding_box.map(|(_, _, _, y_offset)| -(y_offset as i64)))
+            .unwrap_or(0);
+        let height = (ascent + descent).clamp(0, u16::MAX as i64) as u16;
+        let mut font = Self::new(height, height);
+        for (encoding, glyph, x_offset, y_offset) in glyphs {
+            let c = encoding
+                .and_then(|encoding| u32::try_from(encoding).ok())
+                .and_then(char::from_u32);
+            if let Some(c) = c {
+                let offset = |offset: i64| {
+                    i16::try_from(offset).map_err(|_| invalid_data("glyph offset out of range"))
+                };
+                let (x_offset, y_offset) = (offset(x_offset as i64)?, offset(y_offset + ascent)?);
+                font.insert(c, glyph.with_offset(x_offset, y_offset));
+            }
+        }
+        Ok(font.with_fallback(default_char))
+    }
+
+    /// Loads a font from a BDF file. See [`Font::from_bdf()`].
+    pub fn from_bdf_path<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_bdf(&fs::read(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::screen::{Blit, Screen};
+
+    use super::*;
+
+    const FONT: &str = "\
+STARTFONT 2.1
+FONT -test-fixed-medium-r-normal--4-40-75-75-c-30-iso10646-1
+SIZE 4 75 75
+FONTBOUNDINGBOX 3 4 0 -1
+STARTPROPERTIES 3
+FONT_ASCENT 3
+FONT_DESCENT 1
+DEFAULT_CHAR 65533
+ENDPROPERTIES
+CHARS 3
+STARTCHAR j
+ENCODING 106
+SWIDTH 750 0
+DWIDTH 3 0
+BBX 2 4 0 -1
+BITMAP
+40
+40
+40
+80
+ENDCHAR
+STARTCHAR uni00E9
+ENCODING 233
+DWIDTH 4 0
+BBX 3 3 0 0
+BITMAP
+40
+E0
+C0
+ENDCHAR
+STARTCHAR replacement
+ENCODING 65533
+DWIDTH 4 0
+BBX 3 3 0 0
+BITMAP
+E0
+E0
+E0
+ENDCHAR
+ENDFONT
+";
+
+    #[test]
+    fn parse_bdf() {
+        let font = Font::from_bdf(FONT.as_bytes()).unwrap();
+        assert_eq!((font.height(), font.line_height()), (4, 4));
+        let j = font.glyph('j').unwrap();
+        assert_eq!((j.width, j.height, j.advance), (2, 4, 3));
+        assert_eq!((j.x_offset, j.y_offset), (0, 0));
+        assert!(j.get(1, 0) && !j.get(0, 0) && j.get(0, 3));
+        let e = font.glyph('é').unwrap();
+        assert_eq!(e.y_offset, 0);
+        assert_eq!(font.glyph('x'), font.glyph('\u{fffd}'));
+        assert_eq!(font.measure("jé"), (6, 4));
+
+        let mut screen = Screen::new_pixels(4, 4);
+        screen.draw_text("j", 0, 0, &font, Blit::Set, None);
+        assert_eq!(screen.rasterize(), "⡸⠀\n");
+    }
+
+    #[test]
+    fn invalid_bdf() {
+        for data in [
+            "",
+            "STARTFONT 2.1\nSTARTCHAR a\nENCODING 97\n",
+            "STARTFONT 2.1\nSTARTCHAR a\nBBX 1 1 0 0\nBITMAP\nZZ\nENDCHAR\nENDFONT\n",
+            "STARTFONT 2.1\nSTARTCHAR a\nBBX 1 x 0 0\n",
+            "STARTFONT 2.1\nFONT_ASCENT 30000\nSTARTCHAR a\nENCODING 97\nBBX 1 1 0 -30000\nBITMAP\n80\nENDCHAR\nENDFONT\n",
+            "STARTFONT 2.1\nFONT_ASCENT 0\nSTARTCHAR a\nENCODING 97\nBBX 1 1 0 40000\nBITMAP\n80\nENDCHAR\nENDFONT\n",
+            "STARTFONT 2.1\nFONT_ASCENT 0\nSTARTCHAR a\nENCODING 97\nBBX 1 1 40000 0\nBITMAP\n80\nENDCHAR\nENDFONT\n",
+        ] {
+            let error = Font::from_bdf(data.as_bytes()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
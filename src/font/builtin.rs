@@ -0,0 +1,206 @@
+//! Glyph data for the built-in fonts.
+//!
+//! Both fonts cover the printable ASCII characters, from `' '` (0x20) to `'~'` (0x7e).
+
+/// The first character of the built-in fonts.
+pub(super) const FIRST_CHAR: char = ' ';
+
+/// Glyphs of the 3x5 font, 3 bits per row, with the top row in the highest bits. The most
+/// significant bit of each row is its leftmost pixel. Lowercase letters reuse the uppercase glyphs.
+pub(super) const TINY: [u16; 95] = [
+    0b000_000_000_000_000, // ' '
+    0b010_010_010_000_010, // '!'
+    0b101_101_000_000_000, // '"'
+    0b101_111_101_111_101, // '#'
+    0b011_110_010_011_110, // '$'
+    0b100_001_010_100_001, // '%'
+    0b010_101_010_101_011, // '&'
+    0b010_010_000_000_000, // '\''
+    0b001_010_010_010_001, // '('
+    0b100_010_010_010_100, // ')'
+    0b000_101_010_101_000, // '*'
+    0b000_010_111_010_000, // '+'
+    0b000_000_000_010_100, // ','
+    0b000_000_111_000_000, // '-'
+    0b000_000_000_000_010, // '.'
+    0b001_001_010_100_100, // '/'
+    0b111_101_101_101_111, // '0'
+    0b010_110_010_010_111, // '1'
+    0b111_001_111_100_111, // '2'
+    0b111_001_111_001_111, // '3'
+    0b101_101_111_001_001, // '4'
+    0b111_100_111_001_111, // '5'
+    0b111_100_111_101_111, // '6'
+    0b111_001_001_010_010, // '7'
+    0b111_101_111_101_111, // '8'
+    0b111_101_111_001_111, // '9'
+    0b000_010_000_010_000, // ':'
+    0b000_010_000_010_100, // ';'
+    0b001_010_100_010_001, // '<'
+    0b000_111_000_111_000, // '='
+    0b100_010_001_010_100, // '>'
+    0b111_001_011_000_010, // '?'
+    0b010_101_111_100_011, // '@'
+    0b010_101_111_101_101, // 'A'
+    0b110_101_110_101_110, // 'B'
+    0b011_100_100_100_011, // 'C'
+    0b110_101_101_101_110, // 'D'
+    0b111_100_110_100_111, // 'E'
+    0b111_100_110_100_100, // 'F'
+    0b011_100_101_101_011, // 'G'
+    0b101_101_111_101_101, // 'H'
+    0b111_010_010_010_111, // 'I'
+    0b001_001_001_101_010, // 'J'
+    0b101_101_110_101_101, // 'K'
+    0b100_100_100_100_111, // 'L'
+    0b101_111_111_101_101, // 'M'
+    0b110_101_101_101_101, // 'N'
+    0b010_101_101_101_010, // 'O'
+    0b110_101_110_100_100, // 'P'
+    0b010_101_101_110_011, // 'Q'
+    0b110_101_110_101_101, // 'R'
+    0b011_100_010_001_110, // 'S'
+    0b111_010_010_010_010, // 'T'
+    0b101_101_101_101_111, // 'U'
+    0b101_101_101_101_010, // 'V'
+    0b101_101_111_111_101, // 'W'
+    0b101_101_010_101_101, // 'X'
+    0b101_101_010_010_010, // 'Y'
+    0b111_001_010_100_111, // 'Z'
+    0b110_100_100_100_110, // '['
+    0b100_100_010_001_001, // '\\'
+    0b011_001_001_001_011, // ']'
+    0b010_101_000_000_000, // '^'
+    0b000_000_000_000_111, // '_'
+    0b100_010_000_000_000, // '`'
+    0b010_101_111_101_101, // 'a'
+    0b110_101_110_101_110, // 'b'
+    0b011_100_100_100_011, // 'c'
+    0b110_101_101_101_110, // 'd'
+    0b111_100_110_100_111, // 'e'
+    0b111_100_110_100_100, // 'f'
+    0b011_100_101_101_011, // 'g'
+    0b101_101_111_101_101, // 'h'
+    0b111_010_010_010_111, // 'i'
+    0b001_001_001_101_010, // 'j'
+    0b101_101_110_101_101, // 'k'
+    0b100_100_100_100_111, // 'l'
+    0b101_111_111_101_101, // 'm'
+    0b110_101_101_101_101, // 'n'
+    0b010_101_101_101_010, // 'o'
+    0b110_101_110_100_100, // 'p'
+    0b010_101_101_110_011, // 'q'
+    0b110_101_110_101_101, // 'r'
+    0b011_100_010_001_110, // 's'
+    0b111_010_010_010_010, // 't'
+    0b101_101_101_101_111, // 'u'
+    0b101_101_101_101_010, // 'v'
+    0b101_101_111_111_101, // 'w'
+    0b101_101_010_101_101, // 'x'
+    0b101_101_010_010_010, // 'y'
+    0b111_001_010_100_111, // 'z'
+    0b011_010_100_010_011, // '{'
+    0b010_010_010_010_010, // '|'
+    0b110_010_001_010_110, // '}'
+    0b000_011_110_000_000, // '~'
+];
+
+/// Glyphs of the 5x7 font, one byte per column from left to right.
+/// The least significant bit of each column is its top pixel.
+pub(super) const SMALL: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // '!'
+    [0x00, 0x07, 0x00, 0x07, 0x00], // '"'
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // '#'
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // '$'
+    [0x23, 0x13, 0x08, 0x64, 0x62], // '%'
+    [0x36, 0x49, 0x55, 0x22, 0x50], // '&'
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '\''
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // '('
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // ')'
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // '*'
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // '+'
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ','
+    [0x08, 0x08, 0x08, 0x08, 0x08], // '-'
+    [0x00, 0x60, 0x60, 0x00, 0x00], // '.'
+    [0x20, 0x10, 0x08, 0x04, 0x02], // '/'
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // '0'
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // '1'
+    [0x42, 0x61, 0x51, 0x49, 0x46], // '2'
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // '3'
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // '4'
+    [0x27, 0x45, 0x45, 0x45, 0x39], // '5'
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // '6'
+    [0x01, 0x71, 0x09, 0x05, 0x03], // '7'
+    [0x36, 0x49, 0x49, 0x49, 0x36], // '8'
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // '9'
+    [0x00, 0x36, 0x36, 0x00, 0x00], // ':'
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ';'
+    [0x08, 0x14, 0x22, 0x41, 0x00], // '<'
+    [0x14, 0x14, 0x14, 0x14, 0x14], // '='
+    [0x00, 0x41, 0x22, 0x14, 0x08], // '>'
+    [0x02, 0x01, 0x51, 0x09, 0x06], // '?'
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // '@'
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // 'A'
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // 'B'
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // 'C'
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // 'D'
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // 'E'
+    [0x7F, 0x09, 0x09, 0x01, 0x01], // 'F'
+    [0x3E, 0x41, 0x41, 0x51, 0x32], // 'G'
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // 'H'
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // 'I'
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // 'J'
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // 'K'
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // 'L'
+    [0x7F, 0x02, 0x04, 0x02, 0x7F], // 'M'
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // 'N'
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // 'O'
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // 'P'
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // 'Q'
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // 'R'
+    [0x46, 0x49, 0x49, 0x49, 0x31], // 'S'
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // 'T'
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // 'U'
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // 'V'
+    [0x7F, 0x20, 0x18, 0x20, 0x7F], // 'W'
+    [0x63, 0x14, 0x08, 0x14, 0x63], // 'X'
+    [0x03, 0x04, 0x78, 0x04, 0x03], // 'Y'
+    [0x61, 0x51, 0x49, 0x45, 0x43], // 'Z'
+    [0x00, 0x00, 0x7F, 0x41, 0x41], // '['
+    [0x02, 0x04, 0x08, 0x10, 0x20], // '\\'
+    [0x41, 0x41, 0x7F, 0x00, 0x00], // ']'
+    [0x04, 0x02, 0x01, 0x02, 0x04], // '^'
+    [0x40, 0x40, 0x40, 0x40, 0x40], // '_'
+    [0x00, 0x01, 0x02, 0x04, 0x00], // '`'
+    [0x20, 0x54, 0x54, 0x54, 0x78], // 'a'
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // 'b'
+    [0x38, 0x44, 0x44, 0x44, 0x20], // 'c'
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // 'd'
+    [0x38, 0x54, 0x54, 0x54, 0x18], // 'e'
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // 'f'
+    [0x08, 0x14, 0x54, 0x54, 0x3C], // 'g'
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // 'h'
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // 'i'
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // 'j'
+    [0x00, 0x7F, 0x10, 0x28, 0x44], // 'k'
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // 'l'
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // 'm'
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // 'n'
+    [0x38, 0x44, 0x44, 0x44, 0x38], // 'o'
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // 'p'
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // 'q'
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // 'r'
+    [0x48, 0x54, 0x54, 0x54, 0x20], // 's'
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // 't'
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // 'u'
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // 'v'
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // 'w'
+    [0x44, 0x28, 0x10, 0x28, 0x44], // 'x'
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // 'y'
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // 'z'
+    [0x00, 0x08, 0x36, 0x41, 0x00], // '{'
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // '|'
+    [0x00, 0x41, 0x36, 0x08, 0x00], // '}'
+    [0x08, 0x04, 0x08, 0x10, 0x08], // '~'
+];
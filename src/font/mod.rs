@@ -0,0 +1,373 @@
+//! Bitmap fonts for drawing text onto the pixel grid.
+//!
+//! A [`Font`] maps characters to [`Glyph`]s, which are small monochrome bitmaps. Two fonts covering
+//! printable ASCII are built in: [`Font::tiny()`] with 3x5 pixel glyphs, and [`Font::small()`]
+//! with 5x7 pixel glyphs.
+//!
+//! Fonts can also be loaded from BDF files with [`Font::from_bdf()`], and from PSF console fonts
+//! with [`Font::from_psf()`].
+//!
+//! Text can be drawn directly with [`Screen::draw_text()`], or rendered into a [`Sprite`]
+//! with [`Font::render()`] so that it can be drawn repeatedly without laying it out again.
+//!
+//! # Examples
+//!
+//! ```
+//! use ti::font::Font;
+//! use ti::screen::{Blit, Screen};
+//!
+//! let font = Font::tiny();
+//! let mut screen = Screen::new_pixels(16, 8);
+//! screen.draw_text("HI", 0, 0, &font, Blit::Set, None);
+//! assert_eq!(font.measure("HI"), (7, 5));
+//! ```
+mod bdf;
+mod builtin;
+mod psf;
+
+use std::{collections::HashMap, io};
+
+use smallvec::smallvec;
+
+use crate::{
+    cell::Cell,
+    color::{Color, ColoredCell},
+    screen::{Blit, Screen},
+    sprite::Sprite,
+    units::{cell_length, index, pos_components},
+};
+
+/// Creates an error for font data that could not be parsed.
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// A monochrome bitmap for a single character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glyph {
+    /// The width of the bitmap, in pixels.
+    pub width: u16,
+    /// The height of the bitmap, in pixels.
+    pub height: u16,
+    /// The horizontal distance from the start of this glyph to the start of the next one, in pixels.
+    pub advance: u16,
+    /// The horizontal offset of the bitmap from the start of the glyph, in pixels.
+    pub x_offset: i16,
+    /// The vertical offset of the bitmap from the top of the line, in pixels.
+    pub y_offset: i16,
+    /// The pixels of the glyph, row by row.
+    bitmap: Vec<bool>,
+}
+
+impl Glyph {
+    /// Creates a glyph from its pixels, given row by row.
+    ///
+    /// Returns `None` if `bitmap` doesn't contain exactly `width * height` pixels.
+    pub fn new(width: u16, height: u16, advance: u16, bitmap: Vec<bool>) -> Option<Self> {
+        (bitmap.len() == cell_length(width, height)).then_some(Self {
+            width,
+            height,
+            advance,
+            x_offset: 0,
+            y_offset: 0,
+            bitmap,
+        })
+    }
+
+    /// Returns a copy of the glyph with its bitmap offset from the start of the glyph and the top
+    /// of the line, e.g. for glyphs with descenders or a different left side bearing.
+    pub fn with_offset(self, x_offset: i16, y_offset: i16) -> Self {
+        Self {
+            x_offset,
+            y_offset,
+            ..self
+        }
+    }
+
+    /// Returns whether the pixel at the given position is set. Returns `false` if out of bounds.
+    pub fn get(&self, x: u16, y: u16) -> bool {
+        x < self.width && y < self.height && self.bitmap[index(x, y, self.width)]
+    }
+}
+
+/// A set of [`Glyph`]s drawn at a common line height.
+///
+/// # Examples
+///
+/// ```
+/// use ti::font::{Font, Glyph};
+///
+/// let mut font = Font::new(2, 3);
+/// font.insert('.', Glyph::new(1, 2, 2, vec![false, true]).unwrap());
+/// assert_eq!(font.measure(".."), (3, 2));
+/// assert_eq!(font.measure(".\n."), (1, 5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Font {
+    glyphs: HashMap<char, Glyph>,
+    height: u16,
+    line_height: u16,
+    fallback: Option<char>,
+}
+
+impl Font {
+    /// Creates an empty font. `height` is the height of a line of text in pixels, and `line_height`
+    /// is the vertical distance between the start of two consecutive lines.
+    pub fn new(height: u16, line_height: u16) -> Self {
+        Self {
+            glyphs: HashMap::new(),
+            height,
+            line_height,
+            fallback: None,
+        }
+    }
+
+    /// The built-in 3x5 pixel font, covering printable ASCII. Lowercase letters are drawn
+    /// as uppercase, and other characters are drawn as `'?'`.
+    pub fn tiny() -> Self {
+        let mut font = Self::new(5, 6).with_fallback(Some('?'));
+        for (c, bits) in (builtin::FIRST_CHAR..).zip(builtin::TINY) {
+            let bitmap = (0..15).rev().map(|i| bits >> i & 1 == 1).collect();
+            font.insert(c, Glyph::new(3, 5, 4, bitmap).unwrap());
+        }
+        font
+    }
+
+    /// The built-in 5x7 pixel font, covering printable ASCII. Other characters are drawn as `'?'`.
+    ///
+    /// Some lowercase letters have descenders, which extend one pixel below the 7 pixel
+    /// line height, into the spacing between lines.
+    pub fn small() -> Self {
+        let mut font = Self::new(7, 9).with_fallback(Some('?'));
+        for (c, columns) in (builtin::FIRST_CHAR..).zip(builtin::SMALL) {
+            let bitmap = (0..8)
+                .flat_map(|y| columns.map(|column| column >> y & 1 == 1))
+                .collect();
+            font.insert(c, Glyph::new(5, 8, 6, bitmap).unwrap());
+        }
+        font
+    }
+
+    /// Sets the character drawn in place of characters missing from the font.
+    /// When `None`, missing characters are skipped.
+    pub fn with_fallback(mut self, fallback: Option<char>) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Adds a glyph to the font, replacing any previous glyph for the same character.
+    pub fn insert(&mut self, c: char, glyph: Glyph) {
+        self.glyphs.insert(c, glyph);
+    }
+
+    /// Returns the glyph drawn for a character, which is the fallback glyph if the font
+    /// doesn't contain the character. Returns `None` if neither exists.
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs
+            .get(&c)
+            .or_else(|| self.glyphs.get(&self.fallback?))
+    }
+
+    /// Returns the height of a line of text, in pixels.
+    pub const fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the vertical distance between the start of two consecutive lines, in pixels.
+    pub const fn line_height(&self) -> u16 {
+        self.line_height
+    }
+
+    /// Returns the positions of the glyph bitmaps of `text`, relative to the top left corner of the text.
+    fn layout<'a>(&'a self, text: &'a str) -> impl Iterator<Item = (i32, i32, &'a Glyph)> + 'a {
+        text.split('\n').enumerate().flat_map(move |(line, text)| {
+            let y = line as i32 * self.line_height as i32;
+            text.chars()
+                .filter_map(|c| self.glyph(c))
+                .scan(0i32, move |x, glyph| {
+                    let glyph_x = *x + glyph.x_offset as i32;
+                    *x += glyph.advance as i32;
+                    Some((glyph_x, y + glyph.y_offset as i32, glyph))
+                })
+        })
+    }
+
+    /// Returns the `(width, height)` of the text in pixels. Lines are separated by `'\n'`.
+    ///
+    /// The width is the width of the longest line, not including the spacing after its last glyph.
+    /// The height spans from the top of the first line to the bottom of the last one.
+    pub fn measure(&self, text: &str) -> (u16, u16) {
+        let lines = text.split('\n').count() as u16;
+        let width = self
+            .layout(text)
+            .map(|(x, _, glyph)| x + glyph.width as i32)
+            .max()
+            .unwrap_or(0)
+            .clamp(0, u16::MAX as i32) as u16;
+        let height = (lines - 1)
+            .saturating_mul(self.line_height)
+            .saturating_add(self.height);
+        (width, height)
+    }
+
+    /// Renders the text into a sprite, with the given color. The sprite is as large as
+    /// the measured size of the text (see [`Font::measure()`]), rounded up to whole cells.
+    ///
+    /// Pixels of glyphs extending outside of the measured size, such as descenders
+    /// below the last line, are cut off.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::font::Font;
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// let font = Font::tiny();
+    /// let label = font.render("OK", None, 0);
+    /// let mut screen = Screen::new_pixels(8, 8);
+    /// screen.draw_sprite(&label, 1, 1, Blit::Set);
+    /// ```
+    pub fn render(&self, text: &str, color: Option<Color>, priority: u16) -> Sprite {
+        let (width, height) = self.measure(text);
+        let ((cell_x, px_x), (cell_y, px_y)) = pos_components(width, height);
+        let width_cells = cell_x + (px_x != 0) as u16;
+        let height_cells = cell_y + (px_y != 0) as u16;
+        let mut data = smallvec![ColoredCell::default(); cell_length(width_cells, height_cells)];
+        for (x, y, glyph) in self.layout(text) {
+            for (dx, dy) in glyph_pixels(glyph) {
+                let (x, y) = (x + dx as i32, y + dy as i32);
+                let (Ok(x), Ok(y)) = (u16::try_from(x), u16::try_from(y)) else {
+                    continue;
+                };
+                if x >= width || y >= height {
+                    continue;
+                }
+                let ((cell_x, px_x), (cell_y, px_y)) = pos_components(x, y);
+                let colored: &mut ColoredCell = &mut data[index(cell_x, cell_y, width_cells)];
+                // The pixel position is always within the cell
+                colored.cell = colored.cell | Cell::from_bit_position(px_x, px_y).unwrap();
+                colored.color = color;
+            }
+        }
+        Sprite::new(data, width_cells, height_cells, priority)
+    }
+}
+
+/// Returns the positions of the pixels set in a glyph.
+fn glyph_pixels(glyph: &Glyph) -> impl Iterator<Item = (u16, u16)> + '_ {
+    (0..glyph.height)
+        .flat_map(move |y| (0..glyph.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| glyph.get(x, y))
+}
+
+impl Screen {
+    /// Draws text with its top left corner at the pixel `(x, y)`, using the given font.
+    /// Lines are separated by `'\n'`.
+    ///
+    /// The blitting mode applies to the bitmap of each glyph like it does to a sprite, so e.g.
+    /// [`Blit::Set`] also clears the unset pixels of each glyph, while [`Blit::Add`] only draws
+    /// the set pixels. Only cells containing set pixels are colored.
+    ///
+    /// Returns `false` if any part of the text was clipped by the screen boundaries, `true` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::font::Font;
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// let mut screen = Screen::new_pixels(4, 8);
+    /// assert!(screen.draw_text("T", 0, 0, &Font::tiny(), Blit::Set, None));
+    /// assert_eq!(screen.rasterize(), "⢹⠁\n⠈⠀\n");
+    /// ```
+    pub fn draw_text(
+        &mut self,
+        text: &str,
+        x: i32,
+        y: i32,
+        font: &Font,
+        blit: Blit,
+        color: Option<Color>,
+    ) -> bool {
+        let mut drawn = true;
+        for (glyph_x, glyph_y, glyph) in font.layout(text) {
+            let (x, y) = (x + glyph_x, y + glyph_y);
+            for dy in 0..glyph.height {
+                for dx in 0..glyph.width {
+                    let (x, y) = (x + dx as i32, y + dy as i32);
+                    let set = glyph.get(dx, dy);
+                    let pixel_blit = match (blit, set) {
+                        (Blit::Set, true) | (Blit::Unset, false) => Blit::Add,
+                        (Blit::Set, false) | (Blit::Unset, true) => Blit::Subtract,
+                        (_, true) => blit,
+                        (_, false) => continue,
+                    };
+                    drawn &= self.draw_pixel_colored(x, y, pixel_blit, color.filter(|_| set));
+                }
+            }
+        }
+        drawn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_fonts() {
+        for font in [Font::tiny(), Font::small()] {
+            for c in ' '..='~' {
+                assert!(font.glyphs.contains_key(&c));
+            }
+            assert_eq!(font.glyph('\u{e9}'), font.glyph('?'));
+        }
+        let font = Font::small();
+        let a = font.glyph('A').unwrap();
+        let rows: Vec<String> = (0..7)
+            .map(|y| {
+                (0..5)
+                    .map(|x| if a.get(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [".###.", "#...#", "#...#", "#...#", "#####", "#...#", "#...#"]
+        );
+    }
+
+    #[test]
+    fn measure_text() {
+        let font = Font::small();
+        assert_eq!(font.measure(""), (0, 7));
+        assert_eq!(font.measure("ab"), (11, 7));
+        assert_eq!(font.measure("abc\nd"), (17, 16));
+        let font = Font::tiny().with_fallback(None);
+        assert_eq!(font.measure("a\u{e9}b"), (7, 5));
+    }
+
+    #[test]
+    fn text_blits() {
+        let font = Font::tiny();
+        let mut screen = Screen::new_pixels(4, 8);
+        screen.draw_pixel(1, 1, Blit::Set);
+        screen.draw_text("-", 0, 0, &font, Blit::Add, None);
+        assert_eq!(screen.rasterize(), "⠴⠄\n⠀⠀\n");
+        screen.draw_text("-", 0, 0, &font, Blit::Set, None);
+        assert_eq!(screen.rasterize(), "⠤⠄\n⠀⠀\n");
+        assert!(!screen.draw_text("-", 2, 0, &font, Blit::Toggle, None));
+    }
+
+    #[test]
+    fn render_sprite() {
+        let font = Font::tiny();
+        let sprite = font.render("I", None, 0);
+        assert_eq!((sprite.default_width(), sprite.default_height()), (2, 2));
+        let mut screen = Screen::new_pixels(4, 8);
+        screen.draw_sprite(&sprite, 0, 0, Blit::Set);
+        let mut expected = Screen::new_pixels(4, 8);
+        expected.draw_text("I", 0, 0, &font, Blit::Set, None);
+        assert_eq!(screen.rasterize(), expected.rasterize());
+    }
+}
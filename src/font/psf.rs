@@ -0,0 +1,319 @@
+//! Loading PC Screen Font (PSF) console fonts, versions 1 and 2.
+
+use std::{fs, io, path::Path};
+
+#[cfg(feature = "gzip")]
+use {flate2::read::GzDecoder, std::io::Read};
+
+use super::{invalid_data, Font, Glyph};
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF1_MODE_512: u8 = 0x01;
+const PSF1_MODE_HAS_TABLE: u8 = 0x02;
+const PSF1_MODE_HAS_SEQUENCES: u8 = 0x04;
+const PSF1_SEPARATOR: u16 = 0xffff;
+const PSF1_START_SEQUENCE: u16 = 0xfffe;
+
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+const PSF2_HAS_UNICODE_TABLE: u32 = 0x01;
+const PSF2_SEPARATOR: u8 = 0xff;
+const PSF2_START_SEQUENCE: u8 = 0xfe;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The layout of the glyphs in a PSF file, shared by both versions.
+struct Layout {
+    glyph_count: usize,
+    glyph_bytes: usize,
+    width: u16,
+    height: u16,
+    glyphs_start: usize,
+}
+
+/// Reads a little-endian `u32` at the given position.
+fn read_u32(data: &[u8], at: usize) -> io::Result<u32> {
+    data.get(at..at + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| invalid_data("truncated PSF2 header"))
+}
+
+/// Decompresses a font compressed with gzip.
+#[cfg(feature = "gzip")]
+fn gunzip(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decompressed = vec![];
+    GzDecoder::new(data)
+        .read_to_end(&mut decompressed)
+        .map_err(|error| invalid_data(format!("invalid gzip data: {error}")))?;
+    Ok(decompressed)
+}
+
+/// Decompressing fonts requires `flate2`, which is only included with the `gzip` feature.
+#[cfg(not(feature = "gzip"))]
+fn gunzip(_: &[u8]) -> io::Result<Vec<u8>> {
+    Err(invalid_data(
+        "the PSF font is compressed with gzip, and must be decompressed first",
+    ))
+}
+
+/// Returns the characters of each glyph from a PSF1 unicode table.
+fn psf1_table(table: &[u8], glyph_count: usize) -> Vec<Vec<char>> {
+    let mut chars = vec![vec![]; glyph_count];
+    let mut glyph = 0;
+    let mut in_sequence = false;
+    for value in table.chunks_exact(2) {
+        let value = u16::from_le_bytes([value[0], value[1]]);
+        match value {
+            PSF1_SEPARATOR => {
+                glyph += 1;
+                in_sequence = false;
+                if glyph == glyph_count {
+                    break;
+                }
+            }
+            PSF1_START_SEQUENCE => in_sequence = true,
+            // Sequences of combining characters can't be drawn as a single char
+            _ if in_sequence => (),
+            _ => chars[glyph].extend(char::from_u32(value as u32)),
+        }
+    }
+    chars
+}
+
+/// Returns the characters of each glyph from a PSF2 unicode table.
+fn psf2_table(table: &[u8], glyph_count: usize) -> Vec<Vec<char>> {
+    let mut chars = vec![vec![]; glyph_count];
+    for (glyph, entry) in table
+        .split(|&byte| byte == PSF2_SEPARATOR)
+        .take(glyph_count)
+        .enumerate()
+    {
+        let singles = entry
+            .split(|&byte| byte == PSF2_START_SEQUENCE)
+            .next()
+            .unwrap_or_default();
+        chars[glyph].extend(String::from_utf8_lossy(singles).chars());
+    }
+    chars
+}
+
+impl Font {
+    /// Loads a font from the contents of a PSF file, as used by the Linux console.
+    /// Both version 1 and version 2 files are supported.
+    ///
+    /// If the font has a Unicode table, glyphs are mapped to all the characters listed for them.
+    /// Otherwise, each glyph is mapped to the character with the code point of its index.
+    /// The glyph for `'\u{fffd}'` or `'?'` is used for missing characters.
+    ///
+    /// Each glyph advances by its full width, since PSF glyphs include their own spacing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if the data is not a PSF font.
+    /// Fonts shipped with Linux distributions are often compressed with gzip (`.psf.gz`);
+    /// these are decompressed with the `gzip` feature, which is enabled by default,
+    /// and must be decompressed first otherwise.
+    pub fn from_psf(data: &[u8]) -> io::Result<Self> {
+        let decompressed;
+        let data = if data.starts_with(&GZIP_MAGIC) {
+            decompressed = gunzip(data)?;
+            &decompressed[..]
+        } else {
+            data
+        };
+        let layout = if data.starts_with(&PSF1_MAGIC) {
+            let (mode, height) = match data.get(2..4) {
+                Some(&[mode, height]) => (mode, height),
+                _ => return Err(invalid_data("truncated PSF1 header")),
+            };
+            Layout {
+                glyph_count: if mode & PSF1_MODE_512 != 0 { 512 } else { 256 },
+                glyph_bytes: height as usize,
+                width: 8,
+                height: height as u16,
+                glyphs_start: 4,
+            }
+        } else if data.starts_with(&PSF2_MAGIC) {
+            let width = read_u32(data, 28)?;
+            let height = read_u32(data, 24)?;
+            let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+                return Err(invalid_data("PSF2 glyphs are too large"));
+            };
+            Layout {
+                glyph_count: read_u32(data, 16)? as usize,
+                glyph_bytes: read_u32(data, 20)? as usize,
+                width,
+                height,
+                glyphs_start: read_u32(data, 8)? as usize,
+            }
+        } else {
+            return Err(invalid_data("not a PSF font"));
+        };
+
+        if layout.width == 0 || layout.height == 0 {
+            return Err(invalid_data("PSF glyphs are empty"));
+        }
+        let row_bytes = (layout.width as usize).div_ceil(8);
+        if layout.glyph_bytes < row_bytes * layout.height as usize {
+            return Err(invalid_data("PSF glyphs are smaller than their dimensions"));
+        }
+        let glyphs_end = layout
+            .glyph_count
+            .checked_mul(layout.glyph_bytes)
+            .and_then(|size| size.checked_add(layout.glyphs_start))
+            .filter(|&end| end <= data.len())
+            .ok_or_else(|| invalid_data("truncated PSF glyph data"))?;
+        let glyph_data = &data[layout.glyphs_start..glyphs_end];
+        let table = &data[glyphs_end..];
+
+        let chars = if data.starts_with(&PSF1_MAGIC) {
+            if data[2] & (PSF1_MODE_HAS_TABLE | PSF1_MODE_HAS_SEQUENCES) != 0 {
+                Some(psf1_table(table, layout.glyph_count))
+            } else {
+                None
+            }
+        } else if read_u32(data, 12)? & PSF2_HAS_UNICODE_TABLE != 0 {
+            Some(psf2_table(table, layout.glyph_count))
+        } else {
+            None
+        };
+
+        let mut font = Self::new(layout.height, layout.height);
+        for (i, bytes) in glyph_data.chunks_exact(layout.glyph_bytes).enumerate() {
+            let bitmap = (0..layout.height as usize)
+                .flat_map(|y| {
+                    let row = &bytes[y * row_bytes..(y + 1) * row_bytes];
+                    (0..layout.width as usize).map(move |x| row[x / 8] & (0x80 >> (x % 8)) != 0)
+                })
+                .collect();
+            let glyph = Glyph::new(layout.width, layout.height, layout.width, bitmap).unwrap();
+            match &chars {
+                Some(chars) => {
+                    for &c in &chars[i] {
+                        font.insert(c, glyph.clone());
+                    }
+                }
+                // Indices that aren't characters, e.g. surrogates, are skipped
+                None => {
+                    if let Some(c) = char::from_u32(i as u32) {
+                        font.insert(c, glyph);
+                    }
+                }
+            }
+        }
+        let fallback = ['\u{fffd}', '?']
+            .into_iter()
+            .find(|c| font.glyphs.contains_key(c));
+        Ok(font.with_fallback(fallback))
+    }
+
+    /// Loads a font from a PSF file. See [`Font::from_psf()`].
+    pub fn from_psf_path<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_psf(&fs::read(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 4x2 glyph with its top left and bottom right pixels set.
+    const GLYPH: [u8; 2] = [0b1000_0000, 0b0001_0000];
+
+    #[test]
+    fn parse_psf1() {
+        let mut data = vec![0x36, 0x04, PSF1_MODE_HAS_TABLE, 2];
+        for _ in 0..256 {
+            data.extend(GLYPH);
+        }
+        // Glyph 0 is 'a' and 'b', glyph 1 is 'c' and a sequence, the rest are unmapped
+        data.extend([b'a', 0, b'b', 0, 0xff, 0xff]);
+        data.extend([b'c', 0, 0xfe, 0xff, b'e', 0, 0x01, 0x03, 0xff, 0xff]);
+        for _ in 2..256 {
+            data.extend([0xff, 0xff]);
+        }
+        let font = Font::from_psf(&data).unwrap();
+        assert_eq!(font.glyphs.len(), 3);
+        let glyph = font.glyph('b').unwrap();
+        assert_eq!((glyph.width, glyph.height, glyph.advance), (8, 2, 8));
+        assert!(glyph.get(0, 0) && glyph.get(3, 1) && !glyph.get(3, 0));
+        assert!(font.glyph('e').is_none());
+    }
+
+    #[test]
+    fn parse_psf2() {
+        let mut data = PSF2_MAGIC.to_vec();
+        // version, header size, flags, length, glyph size, height, width
+        for value in [0u32, 32, 0, 2, 2, 2, 4] {
+            data.extend(value.to_le_bytes());
+        }
+        data.extend(GLYPH);
+        data.extend([0, 0]);
+        let font = Font::from_psf(&data).unwrap();
+        let glyph = font.glyph('\0').unwrap();
+        assert_eq!((glyph.width, glyph.height, glyph.advance), (4, 2, 4));
+        assert!(glyph.get(0, 0) && glyph.get(3, 1));
+        assert!(font.glyph('\u{1}').is_some());
+        assert!(font.glyph('a').is_none());
+
+        // With a unicode table
+        data[12] = PSF2_HAS_UNICODE_TABLE as u8;
+        data.extend("é".as_bytes());
+        data.extend([PSF2_SEPARATOR]);
+        data.extend("?".as_bytes());
+        data.extend([PSF2_START_SEQUENCE, b'x', PSF2_SEPARATOR]);
+        let font = Font::from_psf(&data).unwrap();
+        assert_eq!(font.glyphs.len(), 2);
+        assert!(font.glyph('é').is_some());
+        assert_eq!(font.glyph('a'), font.glyph('?'));
+    }
+
+    /// Returns a PSF2 font without a unicode table.
+    fn psf2(glyph_count: u32, glyph_bytes: u32, height: u32, width: u32) -> Vec<u8> {
+        let mut data = PSF2_MAGIC.to_vec();
+        for value in [0u32, 32, 0, glyph_count, glyph_bytes, height, width] {
+            data.extend(value.to_le_bytes());
+        }
+        data.resize(32 + (glyph_count * glyph_bytes) as usize, 0);
+        data
+    }
+
+    #[test]
+    fn invalid_psf() {
+        for data in [
+            &[][..],
+            &[0x36, 0x04, 0, 8, 0],
+            &[0x36, 0x04, 0, 0],
+            &psf2(4, 0, 0, 0),
+            &psf2(4, 0, 1, 8),
+            &psf2(4, 1, 0, 8),
+            &[0x1f, 0x8b, 8, 0],
+            b"STARTFONT",
+        ] {
+            let error = Font::from_psf(data).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+
+        // Glyph indices that aren't characters are skipped
+        let font = Font::from_psf(&psf2(0xd801, 1, 1, 8)).unwrap();
+        assert_eq!(font.glyphs.len(), 0xd800);
+        assert!(font.glyph('\u{d7ff}').is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn compressed_psf() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(&psf2(2, 1, 1, 8)).unwrap();
+        let font = Font::from_psf(&encoder.finish().unwrap()).unwrap();
+        assert_eq!(font.glyphs.len(), 2);
+
+        // Only a single layer of compression is undone
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(&[0x1f, 0x8b, 8, 0]).unwrap();
+        let error = Font::from_psf(&encoder.finish().unwrap()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,211 @@
+//! Timing information for rendering loops.
+//!
+//! Contains the [`Frame`] context passed to the tick of [`crate::screen::Screen::run_loop()`],
+//! and the [`LoopConfig`] used to configure that loop.
+
+use std::time::Duration;
+
+use crate::{event::Event, input::InputState};
+
+/// The maximum number of fixed updates in a single frame. If the program can't keep up
+/// with the update rate, the excess time is dropped instead of piling up.
+const MAX_UPDATES_PER_FRAME: u32 = 8;
+
+/// The weight of the newest frame when averaging the measured frame rate.
+const FPS_SMOOTHING: f64 = 0.1;
+
+/// Settings for [`crate::screen::Screen::run_loop()`].
+///
+/// # Examples
+///
+/// ```
+/// use ti::frame::LoopConfig;
+///
+/// // Render 30 times a second, and run the game logic 120 times a second.
+/// let config = LoopConfig::new(30.).with_update_rate(120.);
+/// assert_eq!(config.update_rate, Some(120.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoopConfig {
+    /// The number of frames rendered per second.
+    pub frame_rate: f64,
+    /// The number of fixed updates per second, independent of the frame rate.
+    /// See [`Frame::updates`].
+    pub update_rate: Option<f64>,
+}
+
+impl LoopConfig {
+    /// Creates a new configuration rendering `frame_rate` times a second, without fixed updates.
+    ///
+    /// A frame rate that isn't positive, or is too low to represent, doesn't limit the frame rate at all.
+    pub const fn new(frame_rate: f64) -> Self {
+        Self {
+            frame_rate,
+            update_rate: None,
+        }
+    }
+
+    /// Returns a copy of the configuration running fixed updates `update_rate` times a second.
+    ///
+    /// An update rate that isn't positive and finite disables fixed updates.
+    pub const fn with_update_rate(self, update_rate: f64) -> Self {
+        Self {
+            update_rate: if update_rate > 0. && update_rate.is_finite() {
+                Some(update_rate)
+            } else {
+                None
+            },
+            ..self
+        }
+    }
+
+    /// Returns the duration of a single frame, or zero if the frame rate isn't limited.
+    pub fn frame_duration(&self) -> Duration {
+        period(self.frame_rate).unwrap_or(Duration::ZERO)
+    }
+
+    /// Returns the duration of a single fixed update, if any.
+    pub fn update_duration(&self) -> Option<Duration> {
+        self.update_rate
+            .and_then(period)
+            .filter(|step| !step.is_zero())
+    }
+}
+
+/// Returns the duration of something happening `rate` times a second, or `None` if the rate isn't
+/// positive, or so low that the duration can't be represented.
+fn period(rate: f64) -> Option<Duration> {
+    Duration::try_from_secs_f64(1. / rate)
+        .ok()
+        .filter(|_| rate > 0.)
+}
+
+impl Default for LoopConfig {
+    /// Renders 60 times a second, without fixed updates.
+    fn default() -> Self {
+        Self::new(60.)
+    }
+}
+
+/// Information about the current frame of a rendering loop.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// The number of the frame, starting from 0.
+    pub number: u64,
+    /// The time elapsed since the loop started.
+    pub elapsed: Duration,
+    /// The time elapsed since the previous frame (or since the loop started, for the first frame).
+    pub delta: Duration,
+    /// The measured number of frames per second, smoothed over recent frames.
+    pub fps: f64,
+    /// The number of fixed updates that should be run this frame, if [`LoopConfig::update_rate`]
+    /// is set. Running this many updates of [`Frame::update_step`] each keeps the game logic
+    /// in step with the wall clock, regardless of the frame rate. Always 0 otherwise.
+    pub updates: u32,
+    /// The duration of a single fixed update, if [`LoopConfig::update_rate`] is set.
+    pub update_step: Option<Duration>,
+    /// The fraction of a fixed update that has accumulated but not run yet, from 0 to 1.
+    /// This can be used to interpolate positions between updates.
+    pub alpha: f64,
+    /// The input events that arrived during the frame, in order.
+    pub events: Vec<Event>,
+    /// The keys held down during the frame, updated with [`Frame::events`].
+    pub input: InputState,
+    accumulator: Duration,
+}
+
+impl Frame {
+    /// Creates the context of the first frame of a loop.
+    pub(crate) fn new(config: &LoopConfig) -> Self {
+        Self {
+            number: 0,
+            elapsed: Duration::ZERO,
+            delta: Duration::ZERO,
+            fps: 0.,
+            updates: 0,
+            update_step: config.update_duration(),
+            alpha: 0.,
+            events: vec![],
+            input: InputState::new(),
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// Updates the timing information, for a frame that started `delta` after the previous one.
+    pub(crate) fn advance(&mut self, delta: Duration) {
+        self.delta = delta;
+        self.elapsed += delta;
+        if delta > Duration::ZERO {
+            let fps = 1. / delta.as_secs_f64();
+            self.fps = if self.fps == 0. {
+                fps
+            } else {
+                self.fps * (1. - FPS_SMOOTHING) + fps * FPS_SMOOTHING
+            };
+        }
+        if let Some(step) = self.update_step {
+            self.accumulator += delta;
+            let updates = (self.accumulator.as_nanos() / step.as_nanos()) as u32;
+            self.updates = updates.min(MAX_UPDATES_PER_FRAME);
+            self.accumulator = if updates > MAX_UPDATES_PER_FRAME {
+                Duration::ZERO
+            } else {
+                self.accumulator - step * updates
+            };
+            self.alpha = self.accumulator.as_secs_f64() / step.as_secs_f64();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_timing() {
+        let mut frame = Frame::new(&LoopConfig::new(10.));
+        frame.advance(Duration::from_millis(100));
+        frame.advance(Duration::from_millis(100));
+        assert_eq!(frame.elapsed, Duration::from_millis(200));
+        assert_eq!(frame.delta, Duration::from_millis(100));
+        assert!((frame.fps - 10.).abs() < 1e-9);
+        assert_eq!(frame.updates, 0);
+        assert_eq!(frame.update_step, None);
+    }
+
+    #[test]
+    fn fixed_updates() {
+        let mut frame = Frame::new(&LoopConfig::new(60.).with_update_rate(100.));
+        frame.advance(Duration::from_millis(25));
+        assert_eq!(frame.updates, 2);
+        assert!((frame.alpha - 0.5).abs() < 1e-9);
+        frame.advance(Duration::from_millis(5));
+        assert_eq!(frame.updates, 1);
+        assert!(frame.alpha.abs() < 1e-9);
+        frame.advance(Duration::from_millis(5));
+        assert_eq!(frame.updates, 0);
+    }
+
+    #[test]
+    fn fixed_updates_capped() {
+        let mut frame = Frame::new(&LoopConfig::new(60.).with_update_rate(100.));
+        frame.advance(Duration::from_secs(10));
+        assert_eq!(frame.updates, MAX_UPDATES_PER_FRAME);
+        frame.advance(Duration::from_millis(10));
+        assert_eq!(frame.updates, 1);
+    }
+    #[test]
+    fn invalid_rates() {
+        for rate in [0., -1., f64::NAN, f64::INFINITY, f64::MIN_POSITIVE] {
+            let config = LoopConfig::new(rate).with_update_rate(rate);
+            assert_eq!(config.frame_duration(), Duration::ZERO, "{rate}");
+            assert_eq!(config.update_duration(), None, "{rate}");
+            assert_eq!(Frame::new(&config).update_step, None);
+        }
+        let config = LoopConfig {
+            update_rate: Some(f64::INFINITY),
+            ..LoopConfig::new(f64::INFINITY)
+        };
+        assert_eq!(config.update_duration(), None);
+    }
+}
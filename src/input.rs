@@ -0,0 +1,243 @@
+//! Tracking which keys are held down over time.
+//!
+//! Terminals traditionally only report key presses, not releases. [`InputState`] uses key
+//! release events where the terminal reports them (see [`crate::event::KeyKind`]), and otherwise
+//! guesses when a key was released from the timing of key repeats.
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use crate::event::{Direction, Event, Key, KeyKind};
+
+/// How long a key is considered held after a single press, without repeats. This should be
+/// a bit longer than the usual delay before a terminal starts repeating a held key.
+pub const DEFAULT_INITIAL_TIMEOUT: Duration = Duration::from_millis(550);
+/// How long a key is considered held after a repeat. This should be a bit longer than
+/// the usual interval between repeats of a held key.
+pub const DEFAULT_REPEAT_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The state of a held key.
+#[derive(Debug, Clone, Copy)]
+struct Held {
+    /// When the key was last pressed or repeated.
+    last_seen: Duration,
+    /// Whether the key has been repeated since it was first pressed.
+    repeated: bool,
+}
+
+/// The set of keys held down, and the keys that were pressed or released in the last update.
+///
+/// An up-to-date input state is available in [`crate::frame::Frame::input`] when using
+/// [`crate::screen::Screen::run_loop()`].
+///
+/// Keys are distinguished exactly, e.g. `Key::Char('w')` and `Key::Char('W')` are different keys.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use ti::event::{Event, Key, KeyEvent};
+/// use ti::input::InputState;
+///
+/// let mut input = InputState::new();
+/// input.update(Duration::ZERO, &[Event::Key(KeyEvent::new(Key::Char('d')))]);
+/// assert!(input.just_pressed(Key::Char('d')));
+/// assert_eq!(input.direction_vector_wasd(), (1, 0));
+///
+/// // Without any key repeats or releases, the key is eventually considered released
+/// input.update(Duration::from_secs(1), &[]);
+/// assert!(input.just_released(Key::Char('d')));
+/// assert_eq!(input.direction_vector_wasd(), (0, 0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct InputState {
+    held: HashMap<Key, Held>,
+    pressed: HashSet<Key>,
+    released: HashSet<Key>,
+    releases_reported: bool,
+    /// How long a key is considered held after a single press, if the terminal doesn't report releases.
+    pub initial_timeout: Duration,
+    /// How long a key is considered held after a repeat, if the terminal doesn't report releases.
+    pub repeat_timeout: Duration,
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputState {
+    /// Creates a new input state with no keys held down, using the default timeouts.
+    pub fn new() -> Self {
+        Self {
+            held: HashMap::new(),
+            pressed: HashSet::new(),
+            released: HashSet::new(),
+            releases_reported: false,
+            initial_timeout: DEFAULT_INITIAL_TIMEOUT,
+            repeat_timeout: DEFAULT_REPEAT_TIMEOUT,
+        }
+    }
+
+    /// Updates the state with the events that arrived since the last update. `now` is the current
+    /// time, measured from any fixed starting point, e.g. [`crate::frame::Frame::elapsed`].
+    pub fn update(&mut self, now: Duration, events: &[Event]) {
+        self.pressed.clear();
+        self.released.clear();
+        for event in events {
+            let Event::Key(key_event) = event else {
+                continue;
+            };
+            let key = key_event.key;
+            match key_event.kind {
+                KeyKind::Press | KeyKind::Repeat => {
+                    let repeated = key_event.kind == KeyKind::Repeat;
+                    match self.held.get_mut(&key) {
+                        // Without release events, repeats look like presses
+                        Some(held) => {
+                            held.last_seen = now;
+                            held.repeated = true;
+                        }
+                        None => {
+                            self.held.insert(
+                                key,
+                                Held {
+                                    last_seen: now,
+                                    repeated,
+                                },
+                            );
+                            self.pressed.insert(key);
+                        }
+                    }
+                }
+                KeyKind::Release => {
+                    self.releases_reported = true;
+                    if self.held.remove(&key).is_some() {
+                        self.released.insert(key);
+                    }
+                }
+            }
+        }
+        if !self.releases_reported {
+            let (initial, repeat) = (self.initial_timeout, self.repeat_timeout);
+            let released = &mut self.released;
+            self.held.retain(|&key, held| {
+                let timeout = if held.repeated { repeat } else { initial };
+                let down = now.saturating_sub(held.last_seen) <= timeout;
+                if !down {
+                    released.insert(key);
+                }
+                down
+            });
+        }
+    }
+
+    /// Returns true if the key is currently held down.
+    pub fn is_down(&self, key: Key) -> bool {
+        self.held.contains_key(&key)
+    }
+
+    /// Returns true if the key was pressed since the last update.
+    pub fn just_pressed(&self, key: Key) -> bool {
+        self.pressed.contains(&key)
+    }
+
+    /// Returns true if the key was released since the last update.
+    pub fn just_released(&self, key: Key) -> bool {
+        self.released.contains(&key)
+    }
+
+    /// Returns the keys currently held down, in no particular order.
+    pub fn keys_down(&self) -> impl Iterator<Item = Key> + '_ {
+        self.held.keys().copied()
+    }
+
+    /// Returns true if the terminal has reported key releases, in which case they are used
+    /// instead of guessing from timeouts.
+    pub const fn releases_reported(&self) -> bool {
+        self.releases_reported
+    }
+
+    /// Returns the combined direction of all held direction keys, as an `(x, y)` vector with
+    /// components between -1 and 1. Opposite directions cancel out, and two perpendicular
+    /// directions result in a diagonal.
+    ///
+    /// Includes arrow keys, and a configurable keyset for Up, Left, Down, Right.
+    pub fn direction_vector(&self, up: char, left: char, down: char, right: char) -> (i32, i32) {
+        // Each direction is only counted once, even if e.g. both W and the up arrow are held
+        let directions: HashSet<Direction> = self
+            .keys_down()
+            .filter_map(|key| key.direction(up, left, down, right))
+            .collect();
+        directions
+            .into_iter()
+            .map(Direction::vector)
+            .fold((0, 0), |(x, y), (dx, dy)| (x + dx, y + dy))
+    }
+
+    /// Returns the combined direction of all held direction keys.
+    ///
+    /// Maps arrow keys and WASD keys to their directions. See [`InputState::direction_vector()`].
+    pub fn direction_vector_wasd(&self) -> (i32, i32) {
+        self.direction_vector('w', 'a', 's', 'd')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::KeyEvent;
+
+    use super::*;
+
+    fn key(key: Key, kind: KeyKind) -> Event {
+        Event::Key(KeyEvent {
+            kind,
+            ..KeyEvent::new(key)
+        })
+    }
+
+    #[test]
+    fn timeout_heuristic() {
+        let mut input = InputState::new();
+        let ms = Duration::from_millis;
+        let w = Key::Char('w');
+        input.update(ms(0), &[key(w, KeyKind::Press)]);
+        assert!(input.just_pressed(w) && input.is_down(w));
+        // Waiting for the terminal to start repeating
+        input.update(ms(500), &[]);
+        assert!(!input.just_pressed(w) && input.is_down(w));
+        input.update(ms(520), &[key(w, KeyKind::Press)]);
+        assert!(!input.just_pressed(w) && input.is_down(w));
+        // Repeats have a shorter timeout
+        input.update(ms(600), &[]);
+        assert!(input.is_down(w));
+        input.update(ms(700), &[]);
+        assert!(input.just_released(w) && !input.is_down(w));
+        assert!(!input.releases_reported());
+    }
+
+    #[test]
+    fn release_events() {
+        let mut input = InputState::new();
+        let ms = Duration::from_millis;
+        let up = Key::Up;
+        input.update(
+            ms(0),
+            &[key(up, KeyKind::Press), key(Key::Left, KeyKind::Press)],
+        );
+        assert_eq!(input.direction_vector_wasd(), (-1, -1));
+        input.update(ms(10), &[key(Key::Left, KeyKind::Release)]);
+        assert!(input.releases_reported());
+        assert!(input.just_released(Key::Left));
+        assert_eq!(input.direction_vector_wasd(), (0, -1));
+        // No timeouts once releases are reported
+        input.update(ms(10_000), &[key(up, KeyKind::Repeat)]);
+        assert!(input.is_down(up) && !input.just_pressed(up));
+        input.update(ms(10_010), &[key(Key::Char('s'), KeyKind::Press)]);
+        assert_eq!(input.direction_vector_wasd(), (0, 0));
+    }
+}
@@ -4,9 +4,16 @@
 //!
 //! `ti` renders using unicode Braille characters. In addition to raw pixel output,
 //! it supports writing ANSI terminal colors and sprite drawing.
+pub mod backend;
 pub mod cell;
 pub mod color;
 pub mod event;
+pub mod export;
+pub mod font;
+pub mod frame;
+pub mod input;
 pub mod screen;
+pub mod shapes;
 pub mod sprite;
+pub mod tilemap;
 pub(crate) mod units;
@@ -2,29 +2,21 @@
 //!
 //! Contains the [`Screen`] type and its public interface.
 
-use std::{
-    cmp::Ordering,
-    io::{self, stdout, Write},
-    thread,
-    time::{Duration, Instant},
-};
+use std::{cmp::Ordering, io, ops::ControlFlow, thread, time::Instant};
 
-use crossterm::{
-    cursor::{Hide, MoveTo, MoveToColumn, MoveToRow, Show},
-    event::{poll, read, Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers},
-    style::{ResetColor, SetForegroundColor},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-    ExecutableCommand, QueueableCommand,
-};
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers};
+use unicode_width::UnicodeWidthChar;
 
 pub use crossterm::event;
 
 use crate::{
+    backend::{Backend, CrosstermBackend, Session},
     cell::{Cell, BRAILLE_UTF8_BYTES, PIXEL_HEIGHT, PIXEL_WIDTH},
     color::Color,
     event::Event,
+    frame::{Frame, LoopConfig},
     sprite::Sprite,
-    units::{cell_length, from_index, index, pos_components, px_offset},
+    units::{cell_length, from_index, index, pos_components, px_offset, signed_pos_components},
 };
 
 /// A blit type used to select the type of operation
@@ -92,11 +84,38 @@ pub enum Blit {
 pub struct Screen {
     cells: Vec<Cell>,
     deltas: Vec<Option<Priority<Cell>>>,
+    /// Whether each cell is redrawn at the next render even if no pixels were drawn to it,
+    /// e.g. because its color or text changed.
+    dirty: Vec<bool>,
     colors: Vec<Option<Priority<Color>>>,
+    backgrounds: Vec<Option<Priority<Color>>>,
+    texts: Vec<Option<TextCell>>,
+    /// What the backend shows for each cell, as of the last render. `None` if unknown.
+    front: Vec<Option<Presented>>,
+    /// Whether every cell is written at the next render, see [`Screen::invalidate()`].
+    invalidated: bool,
     width: u16,
     height: u16,
 }
 
+/// The contents of a cell as presented by a backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Presented {
+    /// The character shown, or `None` if the cell is covered by a wide character.
+    c: Option<char>,
+    color: Option<Color>,
+    background: Option<Color>,
+}
+
+/// A character shown in a cell instead of its braille pixels. See [`Screen::draw_char()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextCell {
+    /// The cell shows the character, in the given color.
+    Char { c: char, color: Option<Color> },
+    /// The cell is covered by the second half of a wide character in the cell to its left.
+    Continuation,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Priority<T> {
     pub value: T,
@@ -139,7 +158,12 @@ impl Screen {
         Self {
             cells: vec![Cell::empty(); cell_length(width, height)],
             deltas: vec![None; cell_length(width, height)],
+            dirty: vec![false; cell_length(width, height)],
             colors: vec![None; cell_length(width, height)],
+            backgrounds: vec![None; cell_length(width, height)],
+            texts: vec![None; cell_length(width, height)],
+            front: vec![None; cell_length(width, height)],
+            invalidated: false,
             width,
             height,
         }
@@ -159,11 +183,88 @@ impl Screen {
     /// ```
     pub fn new_pixels(width: u16, height: u16) -> Self {
         Self::new_cells(
-            (width + PIXEL_WIDTH as u16 - 1) / PIXEL_WIDTH as u16,
-            (height + PIXEL_HEIGHT as u16 - 1) / PIXEL_HEIGHT as u16,
+            width.div_ceil(PIXEL_WIDTH as u16),
+            height.div_ceil(PIXEL_HEIGHT as u16),
         )
     }
 
+    /// Create a new empty screen filling the whole terminal attached to the standard output.
+    pub fn new_terminal() -> io::Result<Self> {
+        Self::new_for_backend(&CrosstermBackend::stdout())
+    }
+
+    /// Create a new empty screen with the size of the given [`Backend`]'s output area.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::backend::MemoryBackend;
+    /// use ti::screen::Screen;
+    ///
+    /// let screen = Screen::new_for_backend(&MemoryBackend::new(4, 3)).unwrap();
+    /// assert_eq!(screen.width(), 4);
+    /// assert_eq!(screen.height(), 3);
+    /// ```
+    pub fn new_for_backend<B: Backend + ?Sized>(backend: &B) -> io::Result<Self> {
+        let (width, height) = backend.size()?;
+        Ok(Self::new_cells(width, height))
+    }
+
+    /// Resizes the screen to the given dimensions in cells.
+    ///
+    /// The contents of the screen are kept in place, relative to the top left corner. Any contents
+    /// outside the new dimensions are discarded, and new space is empty. The whole screen will be
+    /// redrawn at the next render, with the colors it was last presented with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// let mut screen = Screen::new_cells(2, 2);
+    /// screen.draw_pixel(2, 4, Blit::Set);
+    /// screen.resize(3, 1);
+    /// assert_eq!(screen.rasterize(), "\u{2800}\u{2800}\u{2800}\n");
+    /// screen.resize(2, 2);
+    /// screen.draw_pixel(2, 4, Blit::Set);
+    /// screen.resize(3, 3);
+    /// assert_eq!(screen.get_pixel(2, 4), Some(true));
+    /// ```
+    pub fn resize(&mut self, width: u16, height: u16) {
+        let mut resized = Self::new_cells(width, height);
+        for y in 0..self.height().min(height) {
+            for x in 0..self.width().min(width) {
+                let old = self.index(x, y);
+                let new = resized.index(x, y);
+                resized.cells[new] = self.cells[old];
+                resized.deltas[new] = self.deltas[old];
+                resized.dirty[new] = self.dirty[old];
+                resized.colors[new] = self.colors[old];
+                resized.backgrounds[new] = self.backgrounds[old];
+                resized.texts[new] = self.texts[old];
+                resized.front[new] = self.front[old];
+            }
+            // A wide character can't be cut in half by the right edge
+            if width < self.width() && width > 0 {
+                let last = resized.index(width - 1, y);
+                if self.texts[self.index(width, y)] == Some(TextCell::Continuation) {
+                    resized.texts[last] = None;
+                    resized.mark_dirty(last);
+                }
+            }
+        }
+        *self = resized;
+        self.invalidate();
+    }
+
+    /// Marks every cell of the screen to be redrawn at the next render, e.g. after the
+    /// terminal was cleared, or before rendering to a different backend.
+    ///
+    /// Cells that aren't drawn to again are redrawn as they were last presented, colors included.
+    pub fn invalidate(&mut self) {
+        self.invalidated = true;
+    }
+
     /// Get the width of the screen, in number of cells.
     /// # Examples
     ///
@@ -220,8 +321,17 @@ impl Screen {
         from_index(i, self.width())
     }
 
+    /// Computes the array index of a cell at a signed cell position, or `None` if the position
+    /// is outside of the screen.
+    fn checked_index(&self, x: i32, y: i32) -> Option<usize> {
+        match (u16::try_from(x), u16::try_from(y)) {
+            (Ok(x), Ok(y)) if x < self.width() && y < self.height() => Some(self.index(x, y)),
+            _ => None,
+        }
+    }
+
     /// Draws a [`Cell`] to the screen at a given cell position. The given x and y positions
-    /// are in terms of cells.
+    /// are in terms of cells, and may be negative.
     ///
     /// This accepts an additional `blit` parameter specifying how
     /// the sprite should be drawn:
@@ -239,6 +349,7 @@ impl Screen {
     /// whether the parameter will be drawn on top of previous cell data.
     ///
     /// Returns `true` if the coordinates were valid, and `false` if the given coordinate was out of bounds.
+    /// Out-of-bounds cells are clipped, i.e. not drawn.
     ///
     /// # Examples
     ///
@@ -253,14 +364,14 @@ impl Screen {
     /// assert_eq!(screen.get_cell(0, 0), Some(cell));
     ///
     /// assert!(!screen.draw_cell(cell, 99, 99, Blit::Set, 0));
+    /// assert!(!screen.draw_cell(cell, -1, 0, Blit::Set, 0));
     /// assert_eq!(screen.get_cell(0, 0), Some(cell));
     ///
     /// assert!(screen.draw_cell(cell, 0, 0, Blit::Toggle, 0));
     /// assert_eq!(screen.get_cell(0, 0), Some(Cell::empty()));
     /// ```
-    pub fn draw_cell(&mut self, cell: Cell, x: u16, y: u16, blit: Blit, priority: u16) -> bool {
-        if x < self.width() && y < self.height() {
-            let index = self.index(x, y);
+    pub fn draw_cell(&mut self, cell: Cell, x: i32, y: i32, blit: Blit, priority: u16) -> bool {
+        if let Some(index) = self.checked_index(x, y) {
             let previous_cell = self.cells[index];
             let new_cell = Cell::new(match blit {
                 Blit::Set => cell.bits,
@@ -300,14 +411,46 @@ impl Screen {
     /// assert!(screen.draw_cell_color(color, 1, 0, 0));
     /// assert_eq!(screen.get_color(1, 0), Some(color));
     /// ```
-    pub fn draw_cell_color(&mut self, color: Color, x: u16, y: u16, priority: u16) -> bool {
-        if x < self.width() && y < self.height() {
-            let i = self.index(x, y);
+    pub fn draw_cell_color(&mut self, color: Color, x: i32, y: i32, priority: u16) -> bool {
+        if let Some(i) = self.checked_index(x, y) {
             let new_color = Priority::new(color, priority);
             self.colors[i] = match self.colors[i] {
                 Some(previous) => Some(previous.max(new_color)),
                 None => Some(new_color),
             };
+            self.mark_dirty(i);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Sets the background color of the cell at the specified position. The background color
+    /// is visible through the unset pixels of the cell.
+    ///
+    /// The `priority` parameter can be used to decide which colors show on top.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::screen::Screen;
+    /// use ti::color::Color;
+    ///
+    /// let mut screen = Screen::new_cells(2, 1);
+    /// let color = Color::new(23);
+    /// assert!(screen.draw_cell_background(color, 1, 0, 0));
+    /// assert!(!screen.draw_cell_background(color, -1, 0, 0));
+    /// assert_eq!(screen.get_background(1, 0), Some(color));
+    /// assert_eq!(screen.get_color(1, 0), None);
+    /// ```
+    pub fn draw_cell_background(&mut self, color: Color, x: i32, y: i32, priority: u16) -> bool {
+        if let Some(i) = self.checked_index(x, y) {
+            let new_color = Priority::new(color, priority);
+            self.backgrounds[i] = match self.backgrounds[i] {
+                Some(previous) => Some(previous.max(new_color)),
+                None => Some(new_color),
+            };
+            self.mark_dirty(i);
             true
         } else {
             false
@@ -322,6 +465,7 @@ impl Screen {
     /// * [`Blit::Toggle`] causes the pixel to be flipped, i.e. turned from a 1 to a 0 and vice versa.
     ///
     /// Returns `true` if the coordinates were valid, and `false` if the given coordinate was out of bounds.
+    /// The coordinates may be negative, in which case nothing is drawn.
     ///
     /// This method always draws with maximum priority.
     ///
@@ -334,8 +478,8 @@ impl Screen {
     /// assert!(screen.draw_pixel(0, 0, Blit::Set));
     /// assert_eq!(screen.get_pixel(0, 0), Some(true));
     /// ```
-    pub fn draw_pixel(&mut self, x: u16, y: u16, blit: Blit) -> bool {
-        let ((x_cell, x_pixel), (y_cell, y_pixel)) = pos_components(x, y);
+    pub fn draw_pixel(&mut self, x: i32, y: i32, blit: Blit) -> bool {
+        let ((x_cell, x_pixel), (y_cell, y_pixel)) = signed_pos_components(x, y);
         // We don't want to influence the other bits
         let blit = match blit {
             Blit::Unset => Blit::Subtract,
@@ -351,15 +495,136 @@ impl Screen {
     /// An extension of [`Screen::draw_pixel()`] that also accepts an optional `color` parameter.
     ///
     /// The priority is still maximum, the return value is the same bool, and blitting works as before.
-    pub fn draw_pixel_colored(&mut self, x: u16, y: u16, blit: Blit, color: Option<Color>) -> bool {
+    pub fn draw_pixel_colored(&mut self, x: i32, y: i32, blit: Blit, color: Option<Color>) -> bool {
         if let Some(color) = color {
-            let ((cell_x, _), (cell_y, _)) = pos_components(x, y);
+            let ((cell_x, _), (cell_y, _)) = signed_pos_components(x, y);
             // ignore result, accounted for in draw_pixel
             self.draw_cell_color(color, cell_x, cell_y, u16::MAX);
         }
         self.draw_pixel(x, y, blit)
     }
 
+    /// Marks the cell at the given index to be redrawn at the next render.
+    fn mark_dirty(&mut self, i: usize) {
+        self.dirty[i] = true;
+    }
+
+    /// Removes the text from the cell at the given index, including the other half of a wide character.
+    fn remove_text(&mut self, i: usize) {
+        let (x, _) = self.from_index(i);
+        match self.texts[i].take() {
+            Some(TextCell::Continuation) if x > 0 => {
+                self.texts[i - 1] = None;
+                self.mark_dirty(i - 1);
+            }
+            Some(TextCell::Char { c, .. }) if c.width() == Some(2) && x + 1 < self.width() => {
+                self.texts[i + 1] = None;
+                self.mark_dirty(i + 1);
+            }
+            _ => (),
+        }
+        self.mark_dirty(i);
+    }
+
+    /// Shows a character in the cell at the specified position, instead of its braille pixels.
+    /// The pixels of the cell are kept, and show up again once the text is removed
+    /// with [`Screen::clear_text()`] or [`Screen::clear()`].
+    ///
+    /// The character is drawn in the given color, regardless of the colors of the pixels in the cell.
+    /// The background color of the cell applies to the text as well.
+    ///
+    /// Wide characters, such as most CJK characters and emoji, cover two cells. The cell to the right
+    /// is covered by a [`TextCell::Continuation`].
+    ///
+    /// Returns `false`, and draws nothing, if the character doesn't fit on the screen, or if it is
+    /// a control character or has no width (like combining characters).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::screen::{Blit, Screen, TextCell};
+    ///
+    /// let mut screen = Screen::new_cells(3, 1);
+    /// screen.draw_pixel(0, 0, Blit::Set);
+    /// assert!(screen.draw_char('字', 1, 0, None));
+    /// assert!(!screen.draw_char('字', 2, 0, None));
+    /// assert_eq!(screen.get_text(2, 0), Some(TextCell::Continuation));
+    /// assert_eq!(screen.rasterize(), "⠁字\n");
+    /// ```
+    pub fn draw_char(&mut self, c: char, x: i32, y: i32, color: Option<Color>) -> bool {
+        let width = match c.width() {
+            Some(width @ 1..=2) => width as i32,
+            _ => return false,
+        };
+        let (Some(i), Some(last)) = (
+            self.checked_index(x, y),
+            self.checked_index(x + width - 1, y),
+        ) else {
+            return false;
+        };
+        for i in i..=last {
+            self.remove_text(i);
+        }
+        self.texts[i] = Some(TextCell::Char { c, color });
+        if width == 2 {
+            self.texts[last] = Some(TextCell::Continuation);
+        }
+        true
+    }
+
+    /// Draws a string of characters starting from the specified cell position, one cell per character
+    /// (or two, for wide characters). See [`Screen::draw_char()`].
+    ///
+    /// The string is drawn on a single line. Characters without a width are skipped.
+    ///
+    /// Returns `false` if any part of the string was clipped by the screen boundaries, `true` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::color::standard;
+    /// use ti::screen::Screen;
+    ///
+    /// let mut screen = Screen::new_cells(8, 1);
+    /// assert!(screen.draw_str("Score: 3", 0, 0, Some(standard::YELLOW)));
+    /// assert_eq!(screen.rasterize(), "Score: 3\n");
+    /// ```
+    pub fn draw_str(&mut self, s: &str, x: i32, y: i32, color: Option<Color>) -> bool {
+        let mut x = x;
+        let mut drawn = true;
+        for c in s.chars() {
+            let Some(width @ 1..=2) = c.width() else {
+                continue;
+            };
+            drawn &= self.draw_char(c, x, y, color);
+            x = x.saturating_add(width as i32);
+        }
+        drawn
+    }
+
+    /// Removes any text from the cell at the specified position, showing its braille pixels again.
+    /// If the cell is part of a wide character, the whole character is removed.
+    ///
+    /// Returns `false` if the position was out of bounds.
+    pub fn clear_text(&mut self, x: i32, y: i32) -> bool {
+        if let Some(i) = self.checked_index(x, y) {
+            self.remove_text(i);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the text shown in the cell at the specified position, if any. Returns `None`
+    /// if out of bounds.
+    pub fn get_text(&self, x: u16, y: u16) -> Option<TextCell> {
+        if x < self.width() && y < self.height() {
+            self.texts[self.index(x, y)]
+        } else {
+            None
+        }
+    }
+
     /// Returns the cell value at the specified (cell) coordinates. Returns None if out of bounds.
     ///
     /// # Examples
@@ -404,6 +669,29 @@ impl Screen {
         }
     }
 
+    /// Returns the background color of the cell at the specified coordinates. Returns None if out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::screen::Screen;
+    /// use ti::color::Color;
+    ///
+    /// let mut screen = Screen::new_cells(2, 2);
+    /// let color = Color::new(123);
+    /// assert_eq!(screen.get_background(999, 999), None);
+    /// screen.draw_cell_background(color, 0, 0, 0);
+    /// assert_eq!(screen.get_background(0, 0), Some(color));
+    /// ```
+    pub fn get_background(&self, x: u16, y: u16) -> Option<Color> {
+        if x < self.width() && y < self.height() {
+            let index = self.index(x, y);
+            self.backgrounds[index].map(|p| p.value)
+        } else {
+            None
+        }
+    }
+
     /// Returns the pixel value at the specified (pixel) coordinates. Returns None if out of bounds.
     ///
     /// # Examples
@@ -427,25 +715,53 @@ impl Screen {
     }
 
     /// Draws a single sprite to the screen. The x and y coordinates are specified in pixels,
-    /// and refer to the top left corner of the sprite.
+    /// and refer to the top left corner of the sprite. They may be negative, e.g. to have
+    /// the sprite slide in from the left or top edge of the screen.
+    ///
+    /// Cells of the sprite with a background color are drawn even if they have no pixels set.
     ///
     /// Returns `false` if any part of the sprite was clipped by the screen boundaries, `true` otherwise.
-    pub fn draw_sprite(&mut self, sprite: &Sprite, x_pixel: u16, y_pixel: u16, blit: Blit) -> bool {
-        let ((dx_cell, x_px), (dy_cell, y_px)) = pos_components(x_pixel, y_pixel);
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::screen::{Screen, Blit};
+    /// use ti::sprite::Sprite;
+    ///
+    /// let mut screen = Screen::new_cells(2, 1);
+    /// let sprite = Sprite::from_braille_string(&["⣿⣿"], None, 0).unwrap();
+    /// assert!(!screen.draw_sprite(&sprite, -3, 0, Blit::Set));
+    /// assert_eq!(screen.rasterize(), "⡇\u{2800}\n");
+    /// ```
+    pub fn draw_sprite(&mut self, sprite: &Sprite, x_pixel: i32, y_pixel: i32, blit: Blit) -> bool {
+        self.draw_sprite_with_priority(sprite, x_pixel, y_pixel, blit, sprite.priority)
+    }
+
+    /// Draws a sprite like [`Screen::draw_sprite()`], but with the given priority instead of the sprite's.
+    pub(crate) fn draw_sprite_with_priority(
+        &mut self,
+        sprite: &Sprite,
+        x_pixel: i32,
+        y_pixel: i32,
+        blit: Blit,
+        priority: u16,
+    ) -> bool {
+        let ((dx_cell, x_px), (dy_cell, y_px)) = signed_pos_components(x_pixel, y_pixel);
         let offset = px_offset(x_px, y_px);
         let data = &sprite.offsets[offset as usize];
         data.iter().enumerate().fold(true, |acc, (i, cell)| {
             let (x_cell, y_cell) = sprite.from_index(i, offset);
-            let x = x_cell + dx_cell;
-            let y = y_cell + dy_cell;
-            if !cell.cell.is_empty() {
-                let drawn = self.draw_cell(cell.cell, x, y, blit, sprite.priority);
+            let x = x_cell as i32 + dx_cell;
+            let y = y_cell as i32 + dy_cell;
+            if !cell.cell.is_empty() || cell.background.is_some() {
+                let mut drawn = self.draw_cell(cell.cell, x, y, blit, priority);
                 if let Some(color) = cell.color {
-                    let colored = self.draw_cell_color(color, x, y, sprite.priority);
-                    acc & drawn & colored
-                } else {
-                    acc & drawn
+                    drawn &= self.draw_cell_color(color, x, y, priority);
                 }
+                if let Some(background) = cell.background {
+                    drawn &= self.draw_cell_background(background, x, y, priority);
+                }
+                acc & drawn
             } else {
                 acc
             }
@@ -457,7 +773,7 @@ impl Screen {
     ///
     /// **Ignores** out-of-bounds input.
     /// This may be preferred when drawing sprites that can partially clip off screen.
-    pub fn set_pixel(&mut self, x: u16, y: u16, value: bool) -> bool {
+    pub fn set_pixel(&mut self, x: i32, y: i32, value: bool) -> bool {
         self.draw_pixel(x, y, if value { Blit::Add } else { Blit::Subtract })
     }
 
@@ -465,15 +781,16 @@ impl Screen {
     ///
     /// **Ignores** out-of-bounds input.
     /// This may be preferred when drawing sprites that can partially clip off screen.
-    pub fn toggle_pixel(&mut self, x: u16, y: u16) -> bool {
+    pub fn toggle_pixel(&mut self, x: i32, y: i32) -> bool {
         self.draw_pixel(x, y, Blit::Toggle)
     }
 
-    /// Clears the whole screen, setting it to empty.
+    /// Clears the whole screen, setting it to empty. This also removes all text.
     pub fn clear(&mut self) {
         for cell in &mut self.cells {
             cell.bits = 0;
         }
+        self.texts.fill(None);
         for delta in &mut self.deltas {
             *delta = Some(Priority::new(Cell::empty(), 0))
         }
@@ -481,147 +798,319 @@ impl Screen {
 
     /// Converts the screen to a utf-8 sequence of bytes that can be rendered in a terminal.
     /// Includes newlines in its output.
+    ///
+    /// Cells showing text contain their character instead, and cells covered by a wide character
+    /// are skipped, so that every row has the same display width.
     pub fn rasterize(&self) -> String {
         // additional + height given for newline chars
-        let mut buf = vec![0; self.cells.len() * BRAILLE_UTF8_BYTES + self.height() as usize];
-        for y in 0..self.height() {
-            for x in 0..self.width() {
-                let i = self.index(x, y);
-                let y = y as usize;
-                // extra newlines also counted here
-                buf[i * 3 + y..(i + 1) * 3 + y].copy_from_slice(&self.cells[i].to_braille_utf8());
+        let mut s =
+            String::with_capacity(self.cells.len() * BRAILLE_UTF8_BYTES + self.height() as usize);
+        for (cells, texts) in self
+            .cells
+            .chunks(self.width().max(1) as usize)
+            .zip(self.texts.chunks(self.width().max(1) as usize))
+            .take(self.height() as usize)
+        {
+            for (cell, text) in cells.iter().zip(texts) {
+                match text {
+                    Some(TextCell::Char { c, .. }) => s.push(*c),
+                    Some(TextCell::Continuation) => (),
+                    None => s.push(cell.to_braille_char()),
+                }
             }
-            let y = y as usize;
-            buf[(y + 1) * (self.width() as usize * 3 + 1) - 1] = b'\n';
+            s.push('\n');
         }
-        let Ok(s) = String::from_utf8(buf) else {
-            unreachable!()
-        };
         s
     }
 
     /// Enters the terminal's alternate screen.
+    ///
+    /// The terminal is only restored by [`Screen::exit_screen()`], or by a panic.
+    /// Prefer a [`Session`], which also restores it on early returns.
     pub fn enter_screen(&self) -> io::Result<()> {
-        stdout().execute(EnterAlternateScreen)?.execute(Hide)?;
-        enable_raw_mode()?;
-        Ok(())
+        CrosstermBackend::stdout().enter()
     }
 
     /// Exit's the terminal's alternate screen.
     pub fn exit_screen(&self) -> io::Result<()> {
-        disable_raw_mode()?;
-        stdout().execute(LeaveAlternateScreen)?.execute(Show)?;
-        Ok(())
+        CrosstermBackend::stdout().exit()
     }
 
     /// Renders the current state of the screen buffer to the terminal.
     pub fn render_screen(&mut self) -> io::Result<()> {
-        let mut stdout = stdout();
-        self.write_screen_to(&mut stdout)
+        self.render_to(&mut CrosstermBackend::stdout())
     }
 
-    /// Renders the current state of the screen to some writable buffer.
-    fn write_screen_to<B: Write>(&mut self, buf: &mut B) -> io::Result<()> {
-        buf.queue(MoveTo(0, 0))?;
-        let mut cur_x = 0;
-        let mut cur_y = 0;
+    /// Renders the current state of the screen to the given [`Backend`].
+    ///
+    /// The screen remembers what it last presented to the backend, and only writes the cells that
+    /// changed since then. For example, clearing the screen and drawing the same contents again
+    /// writes nothing. If the backend's contents change by other means, call [`Screen::invalidate()`]
+    /// to redraw everything.
+    ///
+    /// Rendering ends the frame: the colors and backgrounds drawn since the last render are cleared,
+    /// ready to be drawn again for the next frame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::backend::MemoryBackend;
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// let mut screen = Screen::new_cells(2, 1);
+    /// let mut backend = MemoryBackend::new(2, 1);
+    /// screen.draw_pixel(2, 0, Blit::Set);
+    /// screen.render_to(&mut backend).unwrap();
+    /// assert_eq!(backend.char_at(1, 0), Some('⠁'));
+    /// ```
+    pub fn render_to<B: Backend + ?Sized>(&mut self, backend: &mut B) -> io::Result<()> {
+        let mut cursor = None;
+        // The colors left over from previous output are unknown, so they are always set first
         let mut cur_color = None;
-        for (i, (&delta, &color)) in self.deltas.iter().zip(self.colors.iter()).enumerate() {
-            if let Some(cell) = delta {
-                let (x, y) = self.from_index(i);
-                match (x == cur_x, y == cur_y) {
-                    (true, true) => (),
-                    (true, false) => {
-                        buf.queue(MoveToRow(y))?;
-                    }
-                    (false, true) => {
-                        buf.queue(MoveToColumn(x))?;
-                    }
-                    (false, false) => {
-                        buf.queue(MoveTo(x, y))?;
-                    }
-                }
-                if color != cur_color {
-                    if let Some(color) = color {
-                        buf.queue(SetForegroundColor(color.value.to_crossterm_color()))?;
-                    } else {
-                        buf.queue(ResetColor)?;
-                    }
-                    cur_color = color;
-                }
-                buf.write_all(&cell.value.to_braille_utf8())?;
-                cur_x = x + 1;
-                cur_y = y;
+        let mut cur_background = None;
+        for i in 0..self.cells.len() {
+            let presented = match (self.deltas[i], self.front[i]) {
+                (Some(cell), _) => self.presented(i, cell.value, self.colors[i]),
+                (None, _) if self.dirty[i] => self.presented(i, self.cells[i], self.colors[i]),
+                // Cells that weren't drawn to since the last render are unchanged
+                (None, Some(front)) => front,
+                // Cells that were never presented are assumed to be empty, unless everything is redrawn
+                (None, None) if self.invalidated => self.presented(i, self.cells[i], None),
+                (None, None) => continue,
+            };
+            if !self.invalidated && self.front[i] == Some(presented) {
+                continue;
+            }
+            self.front[i] = Some(presented);
+            // Covered cells are drawn along with the wide character to their left
+            let Presented {
+                c: Some(c),
+                color,
+                background,
+            } = presented
+            else {
+                continue;
+            };
+            let (x, y) = self.from_index(i);
+            if cursor != Some((x, y)) {
+                backend.move_to(x, y)?;
+            }
+            if Some(color) != cur_color {
+                backend.set_color(color)?;
+                cur_color = Some(color);
             }
+            if Some(background) != cur_background {
+                backend.set_background(background)?;
+                cur_background = Some(background);
+            }
+            backend.write(c.encode_utf8(&mut [0; 4]))?;
+            cursor = Some((x + c.width().unwrap_or(1) as u16, y));
+        }
+        self.invalidated = false;
+        self.reset_deltas();
+        backend.flush()
+    }
+
+    /// Computes what a cell presents, given its pixels and pixel color.
+    fn presented(&self, i: usize, cell: Cell, color: Option<Priority<Color>>) -> Presented {
+        let background = self.backgrounds[i].map(|p| p.value);
+        match self.texts[i] {
+            Some(TextCell::Continuation) => Presented {
+                c: None,
+                color: None,
+                background,
+            },
+            Some(TextCell::Char { c, color }) => Presented {
+                c: Some(c),
+                color,
+                background,
+            },
+            None => Presented {
+                c: Some(cell.to_braille_char()),
+                color: color.map(|p| p.value),
+                background,
+            },
         }
-        buf.flush()?;
-        Ok(())
     }
 
     /// Resets the working state of the screen.
     fn reset_deltas(&mut self) {
         self.deltas.fill(None);
+        self.dirty.fill(false);
         self.colors.fill(None);
+        self.backgrounds.fill(None);
     }
 
     /// Handles default events:
     ///
     /// * ctrl+c
-    fn handle_default_events(&self, event: Option<CrosstermEvent>) -> io::Result<bool> {
-        if let Some(CrosstermEvent::Key(KeyEvent {
-            code: KeyCode::Char('c'),
-            modifiers: KeyModifiers::CONTROL,
-            ..
-        })) = event
-        {
-            self.exit_screen()?;
-            Ok(false)
-        } else {
-            Ok(true)
-        }
+    ///
+    /// Returns `false` if the loop should exit.
+    fn handle_default_events(&self, event: &CrosstermEvent) -> bool {
+        !matches!(
+            event,
+            CrosstermEvent::Key(KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            })
+        )
     }
 
-    /// Enters the rendering loop. Renders 60 times a second.
-    pub fn start_loop<F: FnMut(&mut Self, Option<Event>) -> io::Result<()>>(
+    /// Enters the rendering loop. Renders `frame_rate` times a second.
+    ///
+    /// Every input event that arrived during a frame is passed to the tick, in order.
+    ///
+    /// When the terminal is resized, the tick receives an [`Event::Resize`], and the whole
+    /// screen is redrawn afterwards. The screen is not resized automatically; use [`Screen::resize()`]
+    /// in the tick to follow the terminal size.
+    ///
+    /// The loop exits on ctrl+c, or when the tick returns an error, which is printed to stderr.
+    /// See [`Screen::run_loop()`] for a loop with more control.
+    pub fn start_loop<F: FnMut(&mut Self, &[Event]) -> io::Result<()>>(
         &mut self,
         frame_rate: u8,
-        mut tick: F,
+        tick: F,
     ) -> io::Result<()> {
-        self.enter_screen()?;
-        let e = loop {
-            // Event polling
-            let start = Instant::now();
-            let frame = Duration::from_secs_f64(1. / frame_rate as f64);
-            let event = if let Ok(true) = poll(frame) {
-                Some(read()?)
-            } else {
-                None
-            };
-            let end = Instant::now();
-            let elapsed = end.duration_since(start);
-            if elapsed < frame {
-                thread::sleep(frame - elapsed);
-            }
-            if !self.handle_default_events(event.clone())? {
-                break None;
-            };
-            match tick(self, event.and_then(Event::from_crossterm_event)) {
-                Ok(()) => (),
-                Err(e) => break Some(e),
-            };
-            self.render_screen()?;
-            self.reset_deltas();
-        };
-        self.exit_screen()?;
+        self.start_loop_with(&mut CrosstermBackend::stdout(), frame_rate, tick)
+    }
+
+    /// Enters the rendering loop, like [`Screen::start_loop()`], but drawing to and reading
+    /// input from the given [`Backend`].
+    pub fn start_loop_with<B, F>(
+        &mut self,
+        backend: &mut B,
+        frame_rate: u8,
+        mut tick: F,
+    ) -> io::Result<()>
+    where
+        B: Backend + ?Sized,
+        F: FnMut(&mut Self, &[Event]) -> io::Result<()>,
+    {
+        let config = LoopConfig::new(frame_rate as f64);
+        let e = self.run_loop_with(backend, config, |s, frame| {
+            Ok(match tick(s, &frame.events) {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(e) => ControlFlow::Break(e),
+            })
+        })?;
         if let Some(e) = e {
             eprintln!("error: {e}");
         }
         Ok(())
     }
+
+    /// Enters a rendering loop with the given configuration, passing a [`Frame`] context
+    /// with timing information and input events to the tick.
+    ///
+    /// The loop keeps going while the tick returns [`ControlFlow::Continue`]. It exits when the tick
+    /// returns [`ControlFlow::Break`], returning its value, or on ctrl+c, returning `None`.
+    /// Errors from the tick are returned as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::ops::ControlFlow;
+    ///
+    /// use ti::event::Key;
+    /// use ti::frame::LoopConfig;
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// let mut screen = Screen::new_pixels(64, 32);
+    /// let mut x = 0.;
+    /// let config = LoopConfig::new(60.).with_update_rate(100.);
+    /// screen.run_loop(config, |s, frame| {
+    ///     for _ in 0..frame.updates {
+    ///         x = (x + 0.5) % 64.;
+    ///     }
+    ///     s.clear();
+    ///     s.draw_pixel(x as i32, 16, Blit::Set);
+    ///     if frame.events.iter().any(|e| e.pressed() == Some(Key::Esc)) {
+    ///         return Ok(ControlFlow::Break(()));
+    ///     }
+    ///     Ok(ControlFlow::Continue(()))
+    /// }).unwrap();
+    /// ```
+    pub fn run_loop<T, F>(&mut self, config: LoopConfig, tick: F) -> io::Result<Option<T>>
+    where
+        F: FnMut(&mut Self, &Frame) -> io::Result<ControlFlow<T>>,
+    {
+        self.run_loop_with(&mut CrosstermBackend::stdout(), config, tick)
+    }
+
+    /// Enters a rendering loop, like [`Screen::run_loop()`], but drawing to and reading
+    /// input from the given [`Backend`].
+    pub fn run_loop_with<B, T, F>(
+        &mut self,
+        backend: &mut B,
+        config: LoopConfig,
+        tick: F,
+    ) -> io::Result<Option<T>>
+    where
+        B: Backend + ?Sized,
+        F: FnMut(&mut Self, &Frame) -> io::Result<ControlFlow<T>>,
+    {
+        let mut session = Session::enter(backend)?;
+        let result = self.run_frames(&mut *session, config, tick)?;
+        // An error of the loop takes precedence; the session still exits when dropped
+        session.exit()?;
+        Ok(result)
+    }
+
+    /// The body of [`Screen::run_loop_with()`], run between entering and exiting the backend.
+    fn run_frames<B, T, F>(
+        &mut self,
+        backend: &mut B,
+        config: LoopConfig,
+        mut tick: F,
+    ) -> io::Result<Option<T>>
+    where
+        B: Backend + ?Sized,
+        F: FnMut(&mut Self, &Frame) -> io::Result<ControlFlow<T>>,
+    {
+        let frame_duration = config.frame_duration();
+        let mut frame = Frame::new(&config);
+        let mut last = Instant::now();
+        loop {
+            // Event polling: collect everything that arrives during the frame
+            let start = Instant::now();
+            let mut resized = false;
+            frame.events.clear();
+            while let Some(event) =
+                backend.poll_event(frame_duration.saturating_sub(start.elapsed()))?
+            {
+                if !self.handle_default_events(&event) {
+                    return Ok(None);
+                };
+                resized |= matches!(event, CrosstermEvent::Resize(..));
+                frame.events.extend(Event::from_crossterm_event(event));
+            }
+            let elapsed = start.elapsed();
+            if elapsed < frame_duration {
+                thread::sleep(frame_duration - elapsed);
+            }
+
+            let now = Instant::now();
+            frame.advance(now - last);
+            frame.input.update(frame.elapsed, &frame.events);
+            last = now;
+            if let ControlFlow::Break(value) = tick(self, &frame)? {
+                return Ok(Some(value));
+            }
+            if resized {
+                backend.clear()?;
+                self.invalidate();
+            }
+            self.render_to(backend)?;
+            frame.number += 1;
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::backend::MemoryBackend;
+
     use super::*;
 
     #[test]
@@ -700,6 +1189,21 @@ mod tests {
         assert_eq!(screen.rasterize(), "\u{2800}⣤\n\u{2800}⠛\n");
     }
 
+    #[test]
+    fn draw_negative_sprite() {
+        let mut screen = Screen::new_cells(2, 2);
+        let sprite = Sprite::from_braille_string(&["⣿"], None, 0).unwrap();
+        assert!(!screen.draw_sprite(&sprite, -1, -1, Blit::Set));
+        assert_eq!(screen.rasterize(), "⠇\u{2800}\n\u{2800}\u{2800}\n");
+        screen.clear();
+        assert!(!screen.draw_sprite(&sprite, -2, -4, Blit::Set));
+        assert_eq!(screen.rasterize(), "\u{2800}\u{2800}\n\u{2800}\u{2800}\n");
+        screen.clear();
+        assert!(!screen.draw_sprite(&sprite, 3, -2, Blit::Set));
+        assert_eq!(screen.rasterize(), "\u{2800}⠘\n\u{2800}\u{2800}\n");
+        assert!(!screen.set_pixel(-1, 0, true));
+    }
+
     #[test]
     fn toggle_unaligned_cell() {
         let mut screen = Screen::new_cells(2, 2);
@@ -722,4 +1226,291 @@ mod tests {
         assert_eq!(screen.rasterize(), "⢰⠒⢢\n⠸⣀⣸\n");
         screen.draw_sprite(&sprite, 2, 4, Blit::Unset);
     }
+
+    #[test]
+    fn render_to_memory() {
+        let mut screen = Screen::new_cells(3, 2);
+        let mut backend = MemoryBackend::new(3, 2);
+        let sprite = Sprite::from_braille_string(&["⢰⣶"], Some(Color::new(4)), 0).unwrap();
+        screen.draw_sprite(&sprite, 2, 4, Blit::Set);
+        screen.render_to(&mut backend).unwrap();
+        assert_eq!(backend.contents(), "\u{2800}\u{2800}\u{2800}\n\u{2800}⢰⣶\n");
+        assert_eq!(backend.color_at(0, 1), None);
+        assert_eq!(backend.color_at(2, 1), Some(Color::new(4)));
+    }
+
+    #[test]
+    fn render_backgrounds() {
+        let mut screen = Screen::new_cells(3, 1);
+        let mut backend = MemoryBackend::new(3, 1);
+        let sprite = Sprite::from_braille_string(&["⢰\u{2800}"], Some(Color::new(4)), 0)
+            .unwrap()
+            .recolor_background(|_| Some(Color::new(5)));
+        screen.draw_sprite(&sprite, 2, 0, Blit::Set);
+        screen.render_to(&mut backend).unwrap();
+        assert_eq!(backend.contents(), "\u{2800}⢰\u{2800}\n");
+        assert_eq!(backend.background_at(0, 0), None);
+        assert_eq!(backend.background_at(1, 0), Some(Color::new(5)));
+        // The empty cell still carries its background
+        assert_eq!(backend.background_at(2, 0), Some(Color::new(5)));
+        assert_eq!(backend.color_at(2, 0), None);
+    }
+
+    #[test]
+    fn render_background_only() {
+        let mut screen = Screen::new_cells(2, 1);
+        let mut backend = MemoryBackend::new(2, 1);
+        screen.draw_cell_background(Color::new(5), 1, 0, 0);
+        screen.render_to(&mut backend).unwrap();
+        assert_eq!(backend.background_at(1, 0), Some(Color::new(5)));
+        assert_eq!(backend.background_at(0, 0), None);
+    }
+
+    #[test]
+    fn resize_between_frames() {
+        let mut screen = Screen::new_cells(2, 1);
+        let mut backend = MemoryBackend::new(3, 1);
+        screen.draw_pixel_colored(2, 0, Blit::Set, Some(Color::new(4)));
+        screen.draw_cell_background(Color::new(5), 0, 0, 0);
+        screen.render_to(&mut backend).unwrap();
+        assert_eq!(screen.get_color(1, 0), None);
+        // The terminal was resized and cleared
+        backend.clear().unwrap();
+        screen.resize(3, 1);
+        screen.render_to(&mut backend).unwrap();
+        assert_eq!(backend.contents(), "\u{2800}⠁\u{2800}\n");
+        assert_eq!(backend.color_at(1, 0), Some(Color::new(4)));
+        assert_eq!(backend.background_at(0, 0), Some(Color::new(5)));
+    }
+
+    #[test]
+    fn render_only_changes() {
+        let mut screen = Screen::new_cells(3, 1);
+        let mut backend = CrosstermBackend::new(vec![]).with_truecolor(true);
+        let frame = |screen: &mut Screen, backend: &mut CrosstermBackend<Vec<u8>>| {
+            let len = backend.writer().len();
+            screen.render_to(backend).unwrap();
+            backend.writer()[len..].to_vec()
+        };
+        screen.invalidate();
+        screen.draw_pixel(0, 0, Blit::Set);
+        assert_eq!(
+            frame(&mut screen, &mut backend),
+            "\x1b[1;1H\x1b[39m\x1b[49m⠁⠀⠀".as_bytes()
+        );
+        // Redrawing the same contents writes nothing
+        screen.clear();
+        screen.draw_pixel(0, 0, Blit::Set);
+        assert_eq!(frame(&mut screen, &mut backend), b"");
+        // Only the changed cell is written
+        screen.clear();
+        screen.draw_pixel(0, 0, Blit::Set);
+        screen.draw_pixel_colored(4, 0, Blit::Set, Some(Color::rgb(1, 2, 3)));
+        assert_eq!(
+            frame(&mut screen, &mut backend),
+            "\x1b[3G\x1b[38;2;1;2;3m\x1b[49m⠁".as_bytes()
+        );
+        // Untouched cells keep their colors
+        screen.draw_pixel(1, 0, Blit::Set);
+        assert_eq!(
+            frame(&mut screen, &mut backend),
+            "\x1b[1G\x1b[39m\x1b[49m⠉".as_bytes()
+        );
+        screen.invalidate();
+        assert_eq!(
+            frame(&mut screen, &mut backend),
+            "\x1b[1G\x1b[39m\x1b[49m⠉⠀\x1b[38;2;1;2;3m⠁".as_bytes()
+        );
+        // A change of color alone is written
+        screen.draw_cell_color(Color::rgb(4, 5, 6), 1, 0, 0);
+        assert_eq!(
+            frame(&mut screen, &mut backend),
+            "\x1b[2G\x1b[38;2;4;5;6m\x1b[49m⠀".as_bytes()
+        );
+    }
+
+    #[test]
+    fn render_text() {
+        let mut screen = Screen::new_cells(4, 2);
+        let mut backend = MemoryBackend::new(4, 2);
+        screen.draw_cell_color(Color::new(3), 2, 0, 0);
+        screen.draw_cell(Cell::full(), 2, 0, Blit::Set, 0);
+        assert!(screen.draw_str("a字", 0, 0, Some(Color::new(1))));
+        assert!(!screen.draw_str("xyz字", 0, 1, None));
+        screen.render_to(&mut backend).unwrap();
+        assert_eq!(screen.rasterize(), "a字\u{2800}\nxyz\u{2800}\n");
+        assert_eq!(backend.contents(), screen.rasterize());
+        assert_eq!(backend.color_at(1, 0), Some(Color::new(1)));
+        assert_eq!(backend.char_at(2, 0), None);
+
+        // Removing half of a wide character removes all of it, and shows the pixels again
+        screen.clear_text(2, 0);
+        assert_eq!(screen.get_text(1, 0), None);
+        screen.draw_cell_color(Color::new(3), 2, 0, 0);
+        screen.render_to(&mut backend).unwrap();
+        assert_eq!(backend.contents(), "a\u{2800}⣿\u{2800}\nxyz\u{2800}\n");
+        assert_eq!(backend.color_at(2, 0), Some(Color::new(3)));
+
+        // Wide characters cut in half by resizing are removed
+        screen.draw_char('字', 2, 0, None);
+        screen.resize(3, 2);
+        assert_eq!(screen.rasterize(), "a\u{2800}⣿\nxyz\n");
+    }
+
+    #[test]
+    fn render_color_then_set() {
+        let mut screen = Screen::new_cells(2, 1);
+        let mut backend = MemoryBackend::new(2, 1);
+        screen.draw_cell(Cell::full(), 0, 0, Blit::Set, 0);
+        screen.render_to(&mut backend).unwrap();
+        // Changing the color doesn't keep the pixels from being replaced at the same priority
+        screen.draw_cell_color(Color::new(3), 0, 0, 0);
+        screen.draw_cell(Cell::empty(), 0, 0, Blit::Set, 0);
+        screen.render_to(&mut backend).unwrap();
+        assert_eq!(screen.rasterize(), "\u{2800}\u{2800}\n");
+        assert_eq!(backend.contents(), screen.rasterize());
+        assert_eq!(backend.color_at(0, 0), Some(Color::new(3)));
+    }
+
+    #[test]
+    fn render_removed_text_then_set() {
+        let mut screen = Screen::new_cells(3, 1);
+        let mut backend = MemoryBackend::new(3, 1);
+        screen.draw_cell(Cell::full(), 0, 0, Blit::Set, 0);
+        screen.draw_cell(Cell::full(), 1, 0, Blit::Set, 0);
+        screen.draw_char('字', 0, 0, None);
+        screen.render_to(&mut backend).unwrap();
+        assert_eq!(backend.contents(), "字\u{2800}\n");
+        // Both halves of the wide character are replaced by the pixels drawn after removing it
+        screen.clear_text(0, 0);
+        screen.draw_cell(Cell::empty(), 0, 0, Blit::Set, 0);
+        screen.draw_cell(Cell::empty(), 1, 0, Blit::Set, 0);
+        screen.render_to(&mut backend).unwrap();
+        assert_eq!(screen.rasterize(), "\u{2800}\u{2800}\u{2800}\n");
+        assert_eq!(backend.contents(), screen.rasterize());
+    }
+
+    #[test]
+    fn loop_exits_on_ctrl_c() {
+        let mut screen = Screen::new_cells(1, 1);
+        let mut backend = MemoryBackend::new(1, 1);
+        for c in ['x', 'y'] {
+            backend.push_event(CrosstermEvent::Key(KeyEvent::new(
+                KeyCode::Char(c),
+                KeyModifiers::NONE,
+            )));
+        }
+        backend.push_timeout();
+        backend.push_event(CrosstermEvent::Key(KeyEvent::new(
+            KeyCode::Char('z'),
+            KeyModifiers::NONE,
+        )));
+        backend.push_event(CrosstermEvent::Key(KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL,
+        )));
+        let mut ticks = vec![];
+        screen
+            .start_loop_with(&mut backend, 60, |s, events| {
+                ticks.push(events.to_vec());
+                s.draw_cell(Cell::full(), 0, 0, Blit::Set, 0);
+                Ok(())
+            })
+            .unwrap();
+        // All events of a frame are passed at once, and ctrl+c exits immediately
+        let key = |c| Event::Key(crate::event::KeyEvent::new(crate::event::Key::Char(c)));
+        assert_eq!(ticks, vec![vec![key('x'), key('y')]]);
+        assert_eq!(backend.char_at(0, 0), Some('⣿'));
+        assert!(!backend.is_entered());
+    }
+
+    #[test]
+    fn run_loop_breaks() {
+        let mut screen = Screen::new_cells(1, 1);
+        let mut backend = MemoryBackend::new(1, 1);
+        backend.push_event(CrosstermEvent::Key(KeyEvent::new(
+            KeyCode::Esc,
+            KeyModifiers::NONE,
+        )));
+        let mut frames = vec![];
+        let result = screen
+            .run_loop_with(&mut backend, LoopConfig::new(200.), |s, frame| {
+                frames.push((frame.number, frame.events.clone()));
+                s.draw_cell(Cell::full(), 0, 0, Blit::Set, 0);
+                Ok(if frame.number == 2 {
+                    ControlFlow::Break(frame.elapsed)
+                } else {
+                    ControlFlow::Continue(())
+                })
+            })
+            .unwrap();
+        assert_eq!(
+            frames,
+            vec![
+                (
+                    0,
+                    vec![Event::Key(crate::event::KeyEvent::new(
+                        crate::event::Key::Esc
+                    ))]
+                ),
+                (1, vec![]),
+                (2, vec![])
+            ]
+        );
+        assert!(result.unwrap() >= LoopConfig::new(200.).frame_duration() * 3);
+        assert_eq!(backend.char_at(0, 0), Some('⣿'));
+        assert!(!backend.is_entered());
+    }
+
+    #[test]
+    fn run_loop_errors() {
+        let mut screen = Screen::new_cells(1, 1);
+        let mut backend = MemoryBackend::new(1, 1);
+        let result = screen.run_loop_with(&mut backend, LoopConfig::new(200.), |_, _| {
+            io::Result::<ControlFlow<()>>::Err(io::Error::other("oops"))
+        });
+        assert_eq!(result.unwrap_err().to_string(), "oops");
+        assert!(!backend.is_entered());
+    }
+
+    #[test]
+    fn run_loop_panics() {
+        let mut screen = Screen::new_cells(1, 1);
+        let mut backend = MemoryBackend::new(1, 1);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            screen.run_loop_with(&mut backend, LoopConfig::new(200.), |_, _| {
+                panic!("tick panicked");
+                #[allow(unreachable_code)]
+                Ok(ControlFlow::<()>::Continue(()))
+            })
+        }));
+        assert!(result.is_err());
+        assert!(!backend.is_entered());
+    }
+
+    #[test]
+    fn loop_resizes() {
+        let mut screen = Screen::new_cells(1, 1);
+        let mut backend = MemoryBackend::new(1, 1);
+        backend.resize(2, 1);
+        backend.push_timeout();
+        backend.push_event(CrosstermEvent::Key(KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL,
+        )));
+        screen.draw_cell(Cell::full(), 0, 0, Blit::Set, 0);
+        screen
+            .start_loop_with(&mut backend, 60, |s, events| {
+                for &event in events {
+                    if let Event::Resize { width, height } = event {
+                        s.resize(width, height);
+                    }
+                }
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!((screen.width(), screen.height()), (2, 1));
+        // Both the old and the new cells are redrawn after the resize
+        assert_eq!(backend.contents(), "⣿\u{2800}\n");
+    }
 }
@@ -0,0 +1,544 @@
+//! Drawing primitives: lines, rectangles, circles, ellipses, arcs, triangles and polygons.
+//!
+//! The shapes are drawn onto the pixel grid of a [`Screen`] with a [`Blit`] mode and an optional
+//! [`Color`], like [`Screen::draw_pixel_colored()`]. Each pixel of a shape is drawn exactly once,
+//! so e.g. [`Blit::Toggle`] never flips a pixel back where the outline of a shape meets itself.
+//! Pixels outside of the screen are clipped while rasterizing, so shapes may extend far beyond
+//! the screen without slowing down drawing.
+//!
+//! Filled shapes include their outline, i.e. a filled shape covers the same pixels
+//! as its outline and everything enclosed by it.
+//!
+//! # Examples
+//!
+//! ```
+//! use ti::screen::{Blit, Screen};
+//!
+//! let mut screen = Screen::new_pixels(8, 8);
+//! screen.draw_line(0, 0, 7, 7, Blit::Set, None);
+//! screen.fill_circle(4, 4, 2, Blit::Toggle, None);
+//! ```
+
+use std::f64::consts::TAU;
+
+use crate::{
+    cell::{PIXEL_HEIGHT, PIXEL_WIDTH},
+    color::Color,
+    screen::{Blit, Screen},
+};
+
+/// The size of the area that shapes are clipped to, in pixels.
+type Bounds = (i64, i64);
+
+/// Returns the first `n` in `lo..hi` for which `f(n)` is true, given that `f` is false up to some
+/// point and true from then on. Returns `hi` if there is none.
+fn partition_point(mut lo: i64, mut hi: i64, f: impl Fn(i64) -> bool) -> i64 {
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if f(mid) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    lo
+}
+
+/// Returns the pixels of a line between two points, including both end points, that are within
+/// the bounds.
+///
+/// The line steps along its longer axis, and the position on the other axis is computed for each
+/// step, rounding halves away from the start. This gives the same pixels as Bresenham's algorithm,
+/// while only stepping through the part of the line that is within the bounds.
+fn line_points(start: (i64, i64), end: (i64, i64), bounds: Bounds) -> Vec<(i32, i32)> {
+    // Swap the axes of steep lines, so that the line always steps along x
+    let steep = (end.1 - start.1).abs() > (end.0 - start.0).abs();
+    let swap = |(a, b): (i64, i64)| if steep { (b, a) } else { (a, b) };
+    let ((x0, y0), (x1, y1), (width, height)) = (swap(start), swap(end), swap(bounds));
+    let (steps, sx) = ((x1 - x0).abs(), (x1 - x0).signum());
+    let (rise, sy) = ((y1 - y0).abs(), (y1 - y0).signum());
+    // The products don't fit in an i64 for the longest lines
+    let y_at = |n: i64| {
+        let (n, steps, rise) = (n as i128, steps as i128, rise as i128);
+        y0 + sy * ((2 * n * rise + steps) / (2 * steps).max(1)) as i64
+    };
+    // The steps within the bounds on the x axis, then the ones within the bounds on the y axis
+    let (first, last) = if sx < 0 {
+        (x0 - width + 1, x0)
+    } else {
+        (-x0, width - 1 - x0)
+    };
+    let (lo, hi) = (first.max(0), last.min(steps) + 1);
+    let (lo, hi) = if sy < 0 {
+        (
+            partition_point(lo, hi, |n| y_at(n) < height),
+            partition_point(lo, hi, |n| y_at(n) < 0),
+        )
+    } else {
+        (
+            partition_point(lo, hi, |n| y_at(n) >= 0),
+            partition_point(lo, hi, |n| y_at(n) >= height),
+        )
+    };
+    (lo..hi)
+        .map(|n| {
+            let (x, y) = swap((x0 + sx * n, y_at(n)));
+            (x as i32, y as i32)
+        })
+        .collect()
+}
+
+/// Returns how far the outline of an axis-aligned ellipse extends from its center, on the row
+/// `dy` pixels above or below the center. Returns -1 for rows outside of the ellipse.
+///
+/// The outline extends to the furthest pixel for which the point half a pixel closer to the
+/// center row, or the point half a pixel closer to the center column, is inside the ellipse.
+/// This follows the outline along whichever axis it is shallower on.
+fn ellipse_extent(rx: i64, ry: i64, dy: i64) -> i64 {
+    if dy > ry {
+        return -1;
+    }
+    // The products don't fit in an i64 for the largest radii
+    let (rx2, ry2, dy) = ((rx as i128).pow(2), (ry as i128).pow(2), dy as i128);
+    let isqrt = |n: i128| (n as u128).isqrt() as i64;
+    // The largest x with ry²x² + rx²(dy - 1/2)² < rx²ry²
+    let vertical = isqrt((rx2 * (4 * ry2 - (2 * dy - 1).pow(2)) - 1) / (4 * ry2));
+    // The largest x with ry²(x - 1/2)² + rx²dy² <= rx²ry², or 0
+    let horizontal = (isqrt(4 * rx2 * (ry2 - dy * dy) / ry2) + 1) / 2;
+    vertical.max(horizontal)
+}
+
+/// Returns the rows of an axis-aligned ellipse centered on the row `cy` that are within the bounds,
+/// along with the inner and outer distance of its outline from the center column on each row.
+///
+/// The radii must be positive.
+fn ellipse_rows(
+    cy: i64,
+    (rx, ry): (i64, i64),
+    bounds: Bounds,
+) -> impl Iterator<Item = (i64, i64, i64)> {
+    let rows = (cy - ry).max(0)..=(cy + ry).min(bounds.1 - 1);
+    rows.map(move |y| {
+        let dy = (y - cy).abs();
+        let outer = ellipse_extent(rx, ry, dy);
+        // Each row of the outline connects to the row further from the center
+        let inner = outer.min(ellipse_extent(rx, ry, dy + 1) + 1);
+        (y, inner, outer)
+    })
+}
+
+/// Returns the pixels of a row between two x positions (inclusive) that are within the bounds.
+fn row_points(y: i64, x0: i64, x1: i64, bounds: Bounds) -> impl Iterator<Item = (i32, i32)> {
+    (x0.max(0)..=x1.min(bounds.0 - 1)).map(move |x| (x as i32, y as i32))
+}
+
+/// Returns the pixels of the outline of an axis-aligned ellipse that are within the bounds.
+fn ellipse_points((cx, cy): (i64, i64), (rx, ry): (i64, i64), bounds: Bounds) -> Vec<(i32, i32)> {
+    if rx < 0 || ry < 0 {
+        return vec![];
+    }
+    if rx == 0 || ry == 0 {
+        return line_points((cx - rx, cy - ry), (cx + rx, cy + ry), bounds);
+    }
+    ellipse_rows(cy, (rx, ry), bounds)
+        .flat_map(|(y, inner, outer)| {
+            row_points(y, cx - outer, cx - inner, bounds).chain(row_points(
+                y,
+                cx + inner,
+                cx + outer,
+                bounds,
+            ))
+        })
+        .collect()
+}
+
+/// Returns the pixels of a filled axis-aligned ellipse that are within the bounds.
+fn filled_ellipse_points(
+    (cx, cy): (i64, i64),
+    (rx, ry): (i64, i64),
+    bounds: Bounds,
+) -> Vec<(i32, i32)> {
+    if rx <= 0 || ry <= 0 {
+        return ellipse_points((cx, cy), (rx, ry), bounds);
+    }
+    ellipse_rows(cy, (rx, ry), bounds)
+        .flat_map(|(y, _, outer)| row_points(y, cx - outer, cx + outer, bounds))
+        .collect()
+}
+
+/// Returns the pixels of the outline of a closed polygon that are within the bounds.
+fn polygon_points(points: &[(i64, i64)], bounds: Bounds) -> Vec<(i32, i32)> {
+    let mut outline = vec![];
+    for (i, &start) in points.iter().enumerate() {
+        let end = points[(i + 1) % points.len()];
+        outline.extend(line_points(start, end, bounds));
+    }
+    outline
+}
+
+impl Screen {
+    /// Returns the size of the screen in pixels, which shapes are clipped to.
+    fn bounds(&self) -> Bounds {
+        (
+            self.width() as i64 * PIXEL_WIDTH as i64,
+            self.height() as i64 * PIXEL_HEIGHT as i64,
+        )
+    }
+
+    /// Draws each of the given pixels once. The pixels must be on the screen.
+    fn draw_points(&mut self, mut points: Vec<(i32, i32)>, blit: Blit, color: Option<Color>) {
+        points.sort_unstable();
+        points.dedup();
+        for (x, y) in points {
+            self.draw_pixel_colored(x, y, blit, color);
+        }
+    }
+
+    /// Draws a line between the pixels `(x0, y0)` and `(x1, y1)`, including both end points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// let mut screen = Screen::new_pixels(4, 4);
+    /// screen.draw_line(0, 0, 3, 3, Blit::Set, None);
+    /// assert_eq!(screen.rasterize(), "⠑⢄\n");
+    /// ```
+    pub fn draw_line(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        blit: Blit,
+        color: Option<Color>,
+    ) {
+        let (start, end) = ((x0 as i64, y0 as i64), (x1 as i64, y1 as i64));
+        self.draw_points(line_points(start, end, self.bounds()), blit, color);
+    }
+
+    /// Draws the outline of a rectangle, with its top left pixel at `(x, y)`. The rectangle is
+    /// `width` pixels wide and `height` pixels tall. Nothing is drawn if either of them is not positive.
+    pub fn draw_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        blit: Blit,
+        color: Option<Color>,
+    ) {
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let (x0, y0) = (x as i64, y as i64);
+        let (x1, y1) = (x0 + width as i64 - 1, y0 + height as i64 - 1);
+        let corners = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)];
+        self.draw_points(polygon_points(&corners, self.bounds()), blit, color);
+    }
+
+    /// Draws a filled rectangle. See [`Screen::draw_rect()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// let mut screen = Screen::new_pixels(4, 4);
+    /// screen.fill_rect(-1, -1, 4, 3, Blit::Set, None);
+    /// assert_eq!(screen.rasterize(), "⠛⠃\n");
+    /// ```
+    pub fn fill_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        blit: Blit,
+        color: Option<Color>,
+    ) {
+        let bounds = self.bounds();
+        let (x0, y0) = (x as i64, y as i64);
+        let (x1, y1) = (x0 + width as i64 - 1, y0 + height as i64 - 1);
+        let rows = y0.max(0)..=y1.min(bounds.1 - 1);
+        let points = rows.flat_map(|y| row_points(y, x0, x1, bounds));
+        self.draw_points(points.collect(), blit, color);
+    }
+
+    /// Draws the outline of a circle centered on the pixel `(cx, cy)`, with the given radius in pixels.
+    /// A circle with a radius of 0 is a single pixel, and nothing is drawn for negative radii.
+    pub fn draw_circle(&mut self, cx: i32, cy: i32, radius: i32, blit: Blit, color: Option<Color>) {
+        self.draw_ellipse(cx, cy, radius, radius, blit, color);
+    }
+
+    /// Draws a filled circle. See [`Screen::draw_circle()`].
+    pub fn fill_circle(&mut self, cx: i32, cy: i32, radius: i32, blit: Blit, color: Option<Color>) {
+        self.fill_ellipse(cx, cy, radius, radius, blit, color);
+    }
+
+    /// Draws the outline of an axis-aligned ellipse centered on the pixel `(cx, cy)`, with the radii
+    /// `rx` and `ry` in pixels. Nothing is drawn if either radius is negative.
+    pub fn draw_ellipse(
+        &mut self,
+        cx: i32,
+        cy: i32,
+        rx: i32,
+        ry: i32,
+        blit: Blit,
+        color: Option<Color>,
+    ) {
+        let (center, radii) = ((cx as i64, cy as i64), (rx as i64, ry as i64));
+        self.draw_points(ellipse_points(center, radii, self.bounds()), blit, color);
+    }
+
+    /// Draws a filled ellipse. See [`Screen::draw_ellipse()`].
+    pub fn fill_ellipse(
+        &mut self,
+        cx: i32,
+        cy: i32,
+        rx: i32,
+        ry: i32,
+        blit: Blit,
+        color: Option<Color>,
+    ) {
+        let (center, radii) = ((cx as i64, cy as i64), (rx as i64, ry as i64));
+        let points = filled_ellipse_points(center, radii, self.bounds());
+        self.draw_points(points, blit, color);
+    }
+
+    /// Draws the part of a circle outline between two angles, in radians.
+    ///
+    /// An angle of 0 points to the right, and angles increase clockwise on the screen (since the
+    /// y axis points down). The arc is drawn clockwise from `start` to `end`; if they are a full turn
+    /// or more apart, the whole circle is drawn.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::f64::consts::PI;
+    ///
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// // The lower half of a circle
+    /// let mut screen = Screen::new_pixels(8, 8);
+    /// screen.draw_arc(3, 3, 3, 0., PI, Blit::Set, None);
+    /// assert_eq!(screen.get_pixel(3, 6), Some(true));
+    /// assert_eq!(screen.get_pixel(3, 0), Some(false));
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_arc(
+        &mut self,
+        cx: i32,
+        cy: i32,
+        radius: i32,
+        start: f64,
+        end: f64,
+        blit: Blit,
+        color: Option<Color>,
+    ) {
+        let (center, radius) = ((cx as i64, cy as i64), radius as i64);
+        let mut points = ellipse_points(center, (radius, radius), self.bounds());
+        if end - start < TAU {
+            let sweep = (end - start).rem_euclid(TAU);
+            points.retain(|&(x, y)| {
+                let (dx, dy) = (x as i64 - center.0, y as i64 - center.1);
+                let angle = (dy as f64).atan2(dx as f64);
+                (angle - start).rem_euclid(TAU) <= sweep
+            });
+        }
+        self.draw_points(points, blit, color);
+    }
+
+    /// Draws the outline of a triangle with the given corners.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_triangle(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        blit: Blit,
+        color: Option<Color>,
+    ) {
+        self.draw_polygon(&[(x0, y0), (x1, y1), (x2, y2)], blit, color);
+    }
+
+    /// Draws a filled triangle. See [`Screen::draw_triangle()`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn fill_triangle(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        blit: Blit,
+        color: Option<Color>,
+    ) {
+        self.fill_polygon(&[(x0, y0), (x1, y1), (x2, y2)], blit, color);
+    }
+
+    /// Draws the outline of a closed polygon through the given pixels. The last point is connected
+    /// back to the first one. Nothing is drawn if `points` is empty.
+    pub fn draw_polygon(&mut self, points: &[(i32, i32)], blit: Blit, color: Option<Color>) {
+        let points: Vec<_> = points.iter().map(|&(x, y)| (x as i64, y as i64)).collect();
+        self.draw_points(polygon_points(&points, self.bounds()), blit, color);
+    }
+
+    /// Draws a filled polygon. See [`Screen::draw_polygon()`].
+    ///
+    /// The polygon may be concave or self-intersecting; a pixel is filled if its center
+    /// is inside the polygon by the even-odd rule.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::screen::{Blit, Screen};
+    ///
+    /// let mut screen = Screen::new_pixels(4, 4);
+    /// screen.fill_polygon(&[(0, 0), (3, 0), (0, 3)], Blit::Set, None);
+    /// assert_eq!(screen.rasterize(), "⡿⠋\n");
+    /// ```
+    pub fn fill_polygon(&mut self, points: &[(i32, i32)], blit: Blit, color: Option<Color>) {
+        let (width, height) = self.bounds();
+        let points: Vec<_> = points.iter().map(|&(x, y)| (x as i64, y as i64)).collect();
+        let mut filled = polygon_points(&points, (width, height));
+        let min_y = points.iter().map(|&(_, y)| y).min().unwrap_or(0).max(0);
+        let max_y = points.iter().map(|&(_, y)| y).max().unwrap_or(-1);
+        let max_y = max_y.min(height - 1);
+        let mut crossings = vec![];
+        for y in min_y..=max_y {
+            crossings.clear();
+            for (i, &(x0, y0)) in points.iter().enumerate() {
+                let (x1, y1) = points[(i + 1) % points.len()];
+                // Half-open on the y axis, so that vertices shared by two edges count once
+                if (y0 <= y) != (y1 <= y) {
+                    let t = (y - y0) as f64 / (y1 - y0) as f64;
+                    crossings.push(x0 as f64 + t * (x1 - x0) as f64);
+                }
+            }
+            crossings.sort_unstable_by(f64::total_cmp);
+            for span in crossings.chunks_exact(2) {
+                let start = span[0].ceil() as i64;
+                let end = span[1].ceil() as i64 - 1;
+                filled.extend(row_points(y, start, end, (width, height)));
+            }
+        }
+        self.draw_points(filled, blit, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixels(screen: &Screen) -> Vec<String> {
+        (0..screen.height() * PIXEL_HEIGHT as u16)
+            .map(|y| {
+                (0..screen.width() * PIXEL_WIDTH as u16)
+                    .map(|x| {
+                        if screen.get_pixel(x, y).unwrap() {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lines() {
+        let mut screen = Screen::new_pixels(6, 4);
+        screen.draw_line(0, 0, 5, 2, Blit::Set, None);
+        screen.draw_line(5, 3, 5, 3, Blit::Set, None);
+        assert_eq!(pixels(&screen), ["##....", "..##..", "....##", ".....#"]);
+        // Clipped, and toggled only once per pixel
+        screen.draw_line(-2, 3, 10, 3, Blit::Toggle, None);
+        assert_eq!(pixels(&screen)[3], "#####.");
+    }
+
+    #[test]
+    fn circles() {
+        let mut screen = Screen::new_pixels(8, 8);
+        screen.draw_circle(3, 3, 3, Blit::Set, None);
+        assert_eq!(
+            pixels(&screen),
+            [
+                "..###...", ".#...#..", "#.....#.", "#.....#.", "#.....#.", ".#...#..", "..###...",
+                "........",
+            ]
+        );
+        let outline = pixels(&screen);
+        screen.fill_circle(3, 3, 3, Blit::Toggle, None);
+        // Toggling the filled circle erases the outline, and leaves the inside
+        for (outline, filled) in outline.iter().zip(pixels(&screen)) {
+            for (a, b) in outline.chars().zip(filled.chars()) {
+                assert!(!(a == '#' && b == '#'));
+            }
+        }
+        assert_eq!(pixels(&screen)[3], ".#####..");
+    }
+
+    #[test]
+    fn ellipses_and_rects() {
+        let mut screen = Screen::new_pixels(8, 4);
+        screen.draw_ellipse(3, 1, 3, 1, Blit::Set, None);
+        assert_eq!(
+            pixels(&screen),
+            [".#####..", "#.....#.", ".#####..", "........"]
+        );
+        screen.clear();
+        screen.draw_rect(1, 0, 4, 3, Blit::Set, None);
+        screen.fill_rect(6, 2, 10, 10, Blit::Set, None);
+        assert_eq!(
+            pixels(&screen),
+            [".####...", ".#..#...", ".####.##", "......##"]
+        );
+    }
+
+    #[test]
+    fn huge_coordinates() {
+        let mut screen = Screen::new_pixels(4, 4);
+        screen.draw_line(-2_000_000_000, 0, 2_000_000_000, 1, Blit::Set, None);
+        assert_eq!(pixels(&screen), ["....", "####", "....", "...."]);
+        screen.clear();
+        // Only the top of the circle is on the screen
+        screen.draw_circle(2, 1_000_000_003, 1_000_000_000, Blit::Set, None);
+        assert_eq!(pixels(&screen), ["....", "....", "....", "####"]);
+        screen.clear();
+        screen.draw_rect(-1_000, 1, i32::MAX, i32::MAX, Blit::Set, None);
+        screen.draw_ellipse(i32::MIN, i32::MIN, i32::MAX, i32::MAX, Blit::Set, None);
+        assert_eq!(pixels(&screen), ["....", "####", "....", "...."]);
+        screen.fill_circle(0, 0, i32::MAX, Blit::Toggle, None);
+        assert_eq!(pixels(&screen), ["####", "....", "####", "####"]);
+        screen.fill_polygon(
+            &[(i32::MIN, i32::MIN), (i32::MAX, 0), (i32::MIN, i32::MAX)],
+            Blit::Toggle,
+            None,
+        );
+        assert_eq!(pixels(&screen), ["....", "####", "....", "...."]);
+    }
+
+    #[test]
+    fn polygons() {
+        let mut screen = Screen::new_pixels(8, 4);
+        // A concave "V" shape
+        screen.fill_polygon(&[(0, 0), (3, 3), (7, 0), (3, 2)], Blit::Set, None);
+        assert_eq!(
+            pixels(&screen),
+            ["#......#", ".##..##.", "..###...", "...#...."]
+        );
+        screen.clear();
+        screen.draw_triangle(0, 0, 6, 0, 0, 3, Blit::Set, None);
+        assert_eq!(
+            pixels(&screen),
+            ["#######.", "#...##..", "#.##....", "##......"]
+        );
+    }
+}
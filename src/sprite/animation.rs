@@ -0,0 +1,214 @@
+//! Module for animating sprites by switching between frames over time.
+
+use std::time::Duration;
+
+use super::*;
+
+/// How an [`AnimatedSprite`] continues after showing its last frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoopMode {
+    /// Starts over from the first frame.
+    #[default]
+    Loop,
+    /// Plays the frames backwards down to the first frame, then forwards again, and so on.
+    PingPong,
+    /// Stays on the last frame.
+    Once,
+}
+
+/// A sequence of [`Sprite`] frames, each shown for its own duration.
+///
+/// The animation doesn't keep track of time itself. Instead, the frame to draw is looked up
+/// from the time since the animation started, e.g. [`crate::frame::Frame::elapsed`] in
+/// a rendering loop.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use ti::sprite::{AnimatedSprite, LoopMode, Sprite};
+///
+/// let blink = AnimatedSprite::new(LoopMode::Loop)
+///     .with_frame(Sprite::from_braille_string(&["⣿"], None, 0).unwrap(), Duration::from_millis(300))
+///     .with_frame(Sprite::from_braille_string(&["⠀"], None, 0).unwrap(), Duration::from_millis(100));
+/// assert_eq!(blink.frame_index_at(Duration::from_millis(250)), Some(0));
+/// assert_eq!(blink.frame_index_at(Duration::from_millis(350)), Some(1));
+/// assert_eq!(blink.frame_index_at(Duration::from_millis(450)), Some(0));
+/// ```
+///
+/// Drawing the current frame in a rendering loop:
+///
+/// ```no_run
+/// use std::ops::ControlFlow;
+/// # use std::time::Duration;
+///
+/// use ti::frame::LoopConfig;
+/// use ti::screen::{Blit, Screen};
+/// # use ti::sprite::{AnimatedSprite, LoopMode};
+///
+/// # let animation = AnimatedSprite::new(LoopMode::Loop);
+/// let mut screen = Screen::new_pixels(32, 32);
+/// screen.run_loop(LoopConfig::new(30.), |s, frame| {
+///     s.clear();
+///     if let Some(sprite) = animation.frame_at(frame.elapsed) {
+///         s.draw_sprite(sprite, 8, 8, Blit::Set);
+///     }
+///     Ok(ControlFlow::<()>::Continue(()))
+/// }).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnimatedSprite {
+    frames: Vec<(Sprite, Duration)>,
+    /// How the animation continues after its last frame.
+    pub mode: LoopMode,
+}
+
+impl AnimatedSprite {
+    /// Creates an animation without any frames.
+    pub const fn new(mode: LoopMode) -> Self {
+        Self {
+            frames: Vec::new(),
+            mode,
+        }
+    }
+
+    /// Creates an animation showing each of the sprites for the same duration.
+    pub fn from_frames<I: IntoIterator<Item = Sprite>>(
+        sprites: I,
+        duration: Duration,
+        mode: LoopMode,
+    ) -> Self {
+        Self {
+            frames: sprites
+                .into_iter()
+                .map(|sprite| (sprite, duration))
+                .collect(),
+            mode,
+        }
+    }
+
+    /// Returns the animation with a frame added to its end.
+    pub fn with_frame(mut self, sprite: Sprite, duration: Duration) -> Self {
+        self.push_frame(sprite, duration);
+        self
+    }
+
+    /// Adds a frame to the end of the animation.
+    pub fn push_frame(&mut self, sprite: Sprite, duration: Duration) {
+        self.frames.push((sprite, duration));
+    }
+
+    /// Returns the frames of the animation, with their durations.
+    pub fn frames(&self) -> &[(Sprite, Duration)] {
+        &self.frames
+    }
+
+    /// Returns the number of frames in the animation.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns whether the animation has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns the time it takes to show every frame once.
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    /// Returns whether an animation in [`LoopMode::Once`] has reached its last frame
+    /// after the given time. Looping animations never finish.
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        self.mode == LoopMode::Once && elapsed >= self.duration()
+    }
+
+    /// Returns the index of the frame shown at the given time since the animation started,
+    /// or `None` if there are no frames.
+    pub fn frame_index_at(&self, elapsed: Duration) -> Option<usize> {
+        let last = self.frames.len().checked_sub(1)?;
+        // The frames of one cycle of the animation, in order
+        let cycle: Vec<usize> = match self.mode {
+            LoopMode::PingPong => (0..=last).chain((1..last).rev()).collect(),
+            LoopMode::Loop | LoopMode::Once => (0..=last).collect(),
+        };
+        let cycle_length: u128 = cycle.iter().map(|&i| self.frames[i].1.as_nanos()).sum();
+        if cycle_length == 0 {
+            return Some(0);
+        }
+        let mut time = elapsed.as_nanos();
+        match self.mode {
+            LoopMode::Once if time >= cycle_length => return Some(last),
+            LoopMode::Once => (),
+            LoopMode::Loop | LoopMode::PingPong => time %= cycle_length,
+        }
+        for i in cycle {
+            let duration = self.frames[i].1.as_nanos();
+            if time < duration {
+                return Some(i);
+            }
+            time -= duration;
+        }
+        Some(last)
+    }
+
+    /// Returns the frame shown at the given time since the animation started,
+    /// or `None` if there are no frames.
+    pub fn frame_at(&self, elapsed: Duration) -> Option<&Sprite> {
+        self.frame_index_at(elapsed).map(|i| &self.frames[i].0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animation(millis: &[u64], mode: LoopMode) -> AnimatedSprite {
+        millis
+            .iter()
+            .fold(AnimatedSprite::new(mode), |animation, &ms| {
+                animation.with_frame(Sprite::empty(1, 1, 0), Duration::from_millis(ms))
+            })
+    }
+
+    fn indices(animation: &AnimatedSprite, times: &[u64]) -> Vec<Option<usize>> {
+        times
+            .iter()
+            .map(|&ms| animation.frame_index_at(Duration::from_millis(ms)))
+            .collect()
+    }
+
+    #[test]
+    fn loop_modes() {
+        let times = [0, 10, 15, 30, 45, 60, 75, 90];
+        let looping = animation(&[10, 20, 10], LoopMode::Loop);
+        assert_eq!(
+            indices(&looping, &times),
+            [0, 1, 1, 2, 0, 1, 2, 1].map(Some)
+        );
+        let ping_pong = animation(&[10, 20, 10], LoopMode::PingPong);
+        assert_eq!(
+            indices(&ping_pong, &times),
+            [0, 1, 1, 2, 1, 0, 1, 2].map(Some)
+        );
+        let once = animation(&[10, 20, 10], LoopMode::Once);
+        assert_eq!(indices(&once, &times), [0, 1, 1, 2, 2, 2, 2, 2].map(Some));
+        assert!(!once.is_finished(Duration::from_millis(39)));
+        assert!(once.is_finished(Duration::from_millis(40)));
+        assert!(!looping.is_finished(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn degenerate_animations() {
+        assert_eq!(
+            AnimatedSprite::default().frame_index_at(Duration::ZERO),
+            None
+        );
+        for mode in [LoopMode::Loop, LoopMode::PingPong, LoopMode::Once] {
+            assert_eq!(indices(&animation(&[10], mode), &[0, 25]), [Some(0); 2]);
+            assert_eq!(indices(&animation(&[0, 0], mode), &[0, 25]), [Some(0); 2]);
+        }
+    }
+}
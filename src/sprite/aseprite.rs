@@ -0,0 +1,882 @@
+//! Module for reading sprites out of Aseprite files.
+
+use super::*;
+
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::Duration;
+
+use flate2::read::ZlibDecoder;
+use image::{DynamicImage, RgbaImage};
+
+const FILE_MAGIC: u16 = 0xa5e0;
+const FRAME_MAGIC: u16 = 0xf1fa;
+const HEADER_SIZE: usize = 128;
+const CHUNK_HEADER_SIZE: usize = 6;
+
+const OLD_PALETTE_CHUNK: u16 = 0x0004;
+const LAYER_CHUNK: u16 = 0x2004;
+const CEL_CHUNK: u16 = 0x2005;
+const TAGS_CHUNK: u16 = 0x2018;
+const PALETTE_CHUNK: u16 = 0x2019;
+
+const HEADER_LAYER_OPACITY_VALID: u32 = 0x01;
+const LAYER_VISIBLE: u16 = 0x01;
+const LAYER_BACKGROUND: u16 = 0x08;
+const LAYER_GROUP: u16 = 1;
+const PALETTE_ENTRY_HAS_NAME: u16 = 0x01;
+/// Indexed pixels are a single byte, so they can't use larger palettes
+const MAX_PALETTE_SIZE: usize = 256;
+
+const CEL_RAW: u16 = 0;
+const CEL_LINKED: u16 = 1;
+const CEL_COMPRESSED: u16 = 2;
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Reads little-endian values from the contents of a file.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if n > self.data.len() {
+            return Err(invalid_data("truncated Aseprite file"));
+        }
+        let (bytes, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn i16(&mut self) -> io::Result<i16> {
+        Ok(i16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        let length = self.u16()? as usize;
+        Ok(String::from_utf8_lossy(self.bytes(length)?).into_owned())
+    }
+}
+
+/// How the pixels of an Aseprite file are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColorDepth {
+    Rgba,
+    Grayscale,
+    Indexed,
+}
+
+impl ColorDepth {
+    const fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::Rgba => 4,
+            Self::Grayscale => 2,
+            Self::Indexed => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Layer {
+    name: String,
+    /// Whether the layer and all of the groups containing it are visible.
+    visible: bool,
+    group: bool,
+    background: bool,
+    opacity: u8,
+}
+
+#[derive(Debug, Clone)]
+struct Cel {
+    layer: usize,
+    x: i32,
+    y: i32,
+    opacity: u8,
+    z_index: i16,
+    width: u32,
+    /// The pixels of the cel, in the color depth of the file.
+    pixels: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Frame {
+    duration: Duration,
+    cels: Vec<Cel>,
+}
+
+/// The order in which the frames of an Aseprite [`Tag`] are played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagDirection {
+    /// From the first frame to the last.
+    Forward,
+    /// From the last frame to the first.
+    Reverse,
+    /// Forwards, then backwards.
+    PingPong,
+    /// Backwards, then forwards.
+    PingPongReverse,
+}
+
+/// A named range of frames in an Aseprite file, usually one animation of a character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    /// The name of the tag.
+    pub name: String,
+    /// The index of the first frame of the tag.
+    pub from: usize,
+    /// The index of the last frame of the tag, inclusive.
+    pub to: usize,
+    /// The order in which the frames are played.
+    pub direction: TagDirection,
+}
+
+/// An image opened from an Aseprite (`.aseprite` or `.ase`) file, with its layers, frames and tags.
+///
+/// Sprites are read from the frames like in [`Atlas`]: colors are converted according to
+/// the `color_mode`, and the alpha channel determines which pixels are set if `use_alpha_channel`
+/// is `true`. Frames can be read with all visible layers blended together, or one layer at a time.
+///
+/// RGBA, grayscale and indexed color files are supported. Tilemap layers are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ti::sprite::{Aseprite, ColorMode};
+///
+/// let file = Aseprite::open("knight.aseprite", ColorMode::TrueColor, true).unwrap();
+/// let walk = file.tag_animation("walk", None, 1, 0).unwrap();
+/// let shadow = file.sprite(0, Some("shadow"), 1, 0).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Aseprite {
+    width: u16,
+    height: u16,
+    depth: ColorDepth,
+    transparent_index: u8,
+    palette: Vec<[u8; 4]>,
+    layers: Vec<Layer>,
+    frames: Vec<Frame>,
+    tags: Vec<Tag>,
+    /// A setting to determine how sprites are read from this file
+    pub color_mode: ColorMode,
+    /// A setting to determine how sprites are read from this file
+    pub use_alpha_channel: bool,
+}
+
+impl Aseprite {
+    /// Opens an Aseprite file from a file path.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        color_mode: ColorMode,
+        use_alpha_channel: bool,
+    ) -> io::Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?, color_mode, use_alpha_channel)
+    }
+
+    /// Parses the contents of an Aseprite file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if the data is not a valid Aseprite file.
+    pub fn from_bytes(
+        data: &[u8],
+        color_mode: ColorMode,
+        use_alpha_channel: bool,
+    ) -> io::Result<Self> {
+        let mut header = Reader {
+            data: data
+                .get(..HEADER_SIZE)
+                .ok_or_else(|| invalid_data("truncated Aseprite header"))?,
+        };
+        header.u32()?;
+        if header.u16()? != FILE_MAGIC {
+            return Err(invalid_data("not an Aseprite file"));
+        }
+        let frame_count = header.u16()?;
+        let width = header.u16()?;
+        let height = header.u16()?;
+        let depth = match header.u16()? {
+            32 => ColorDepth::Rgba,
+            16 => ColorDepth::Grayscale,
+            8 => ColorDepth::Indexed,
+            depth => return Err(invalid_data(format!("unsupported color depth {depth}"))),
+        };
+        let flags = header.u32()?;
+        header.bytes(10)?;
+        let transparent_index = header.u8()?;
+
+        let mut file = Self {
+            width,
+            height,
+            depth,
+            transparent_index,
+            palette: vec![],
+            layers: vec![],
+            frames: vec![],
+            tags: vec![],
+            color_mode,
+            use_alpha_channel,
+        };
+        // The visibility of the groups containing the next layer
+        let mut groups_visible = vec![];
+        let mut reader = Reader {
+            data: &data[HEADER_SIZE..],
+        };
+        for _ in 0..frame_count {
+            let size = reader.u32()? as usize;
+            let mut frame_reader = Reader {
+                data: reader.bytes(size.saturating_sub(4))?,
+            };
+            if frame_reader.u16()? != FRAME_MAGIC {
+                return Err(invalid_data("invalid Aseprite frame"));
+            }
+            let old_chunk_count = frame_reader.u16()?;
+            let duration = Duration::from_millis(frame_reader.u16()? as u64);
+            frame_reader.bytes(2)?;
+            let chunk_count = match frame_reader.u32()? {
+                0 => old_chunk_count as u32,
+                count => count,
+            };
+            let mut frame = Frame {
+                duration,
+                cels: vec![],
+            };
+            for _ in 0..chunk_count {
+                let size = frame_reader.u32()? as usize;
+                let kind = frame_reader.u16()?;
+                let mut chunk = Reader {
+                    data: frame_reader.bytes(size.saturating_sub(CHUNK_HEADER_SIZE))?,
+                };
+                match kind {
+                    LAYER_CHUNK => {
+                        let layer_flags = chunk.u16()?;
+                        let layer_type = chunk.u16()?;
+                        let level = chunk.u16()? as usize;
+                        chunk.bytes(6)?;
+                        let opacity = chunk.u8()?;
+                        chunk.bytes(3)?;
+                        let name = chunk.string()?;
+                        groups_visible.truncate(level);
+                        let visible = layer_flags & LAYER_VISIBLE != 0
+                            && groups_visible.iter().all(|&visible| visible);
+                        let group = layer_type == LAYER_GROUP;
+                        if group {
+                            groups_visible.push(visible);
+                        }
+                        file.layers.push(Layer {
+                            name,
+                            visible,
+                            group,
+                            background: layer_flags & LAYER_BACKGROUND != 0,
+                            opacity: if flags & HEADER_LAYER_OPACITY_VALID != 0 {
+                                opacity
+                            } else {
+                                255
+                            },
+                        });
+                    }
+                    CEL_CHUNK => {
+                        if let Some(cel) = file.parse_cel(&mut chunk)? {
+                            frame.cels.push(cel);
+                        }
+                    }
+                    TAGS_CHUNK => {
+                        let count = chunk.u16()?;
+                        chunk.bytes(8)?;
+                        for _ in 0..count {
+                            let from = chunk.u16()? as usize;
+                            let to = chunk.u16()? as usize;
+                            let direction = match chunk.u8()? {
+                                1 => TagDirection::Reverse,
+                                2 => TagDirection::PingPong,
+                                3 => TagDirection::PingPongReverse,
+                                _ => TagDirection::Forward,
+                            };
+                            chunk.bytes(12)?;
+                            let name = chunk.string()?;
+                            file.tags.push(Tag {
+                                name,
+                                from,
+                                to,
+                                direction,
+                            });
+                        }
+                    }
+                    PALETTE_CHUNK => {
+                        let size = chunk.u32()? as usize;
+                        let first = chunk.u32()? as usize;
+                        let last = chunk.u32()? as usize;
+                        chunk.bytes(8)?;
+                        file.check_palette_size(size)?;
+                        let size = size.min(MAX_PALETTE_SIZE);
+                        file.palette.resize(size.max(file.palette.len()), [0; 4]);
+                        for i in first..=last {
+                            let entry_flags = chunk.u16()?;
+                            let color = chunk.bytes(4)?.try_into().unwrap();
+                            if let Some(entry) = file.palette.get_mut(i) {
+                                *entry = color;
+                            }
+                            if entry_flags & PALETTE_ENTRY_HAS_NAME != 0 {
+                                chunk.string()?;
+                            }
+                        }
+                    }
+                    // Only used if there is no newer palette chunk
+                    OLD_PALETTE_CHUNK if file.palette.is_empty() => {
+                        let mut i = 0;
+                        for _ in 0..chunk.u16()? {
+                            i += chunk.u8()? as usize;
+                            let count = match chunk.u8()? {
+                                0 => 256,
+                                count => count as usize,
+                            };
+                            for _ in 0..count {
+                                let [r, g, b] = chunk.bytes(3)?.try_into().unwrap();
+                                file.check_palette_size(i + 1)?;
+                                if i < MAX_PALETTE_SIZE {
+                                    if file.palette.len() <= i {
+                                        file.palette.resize(i + 1, [0; 4]);
+                                    }
+                                    file.palette[i] = [r, g, b, 255];
+                                }
+                                i += 1;
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            file.frames.push(frame);
+        }
+        Ok(file)
+    }
+
+    /// Checks that a palette of the given size can be used. Only indexed images use the palette,
+    /// so the colors beyond what they can use are ignored for other color depths.
+    fn check_palette_size(&self, size: usize) -> io::Result<()> {
+        if self.depth == ColorDepth::Indexed && size > MAX_PALETTE_SIZE {
+            return Err(invalid_data("Aseprite palette is too large"));
+        }
+        Ok(())
+    }
+
+    /// Parses a cel chunk. Returns `None` for cels that can't be drawn, such as tilemaps.
+    fn parse_cel(&self, chunk: &mut Reader) -> io::Result<Option<Cel>> {
+        let layer = chunk.u16()? as usize;
+        let x = chunk.i16()? as i32;
+        let y = chunk.i16()? as i32;
+        let opacity = chunk.u8()?;
+        let cel_type = chunk.u16()?;
+        let z_index = chunk.i16()?;
+        chunk.bytes(5)?;
+        let cel = match cel_type {
+            CEL_RAW | CEL_COMPRESSED => {
+                let width = chunk.u16()? as u32;
+                let height = chunk.u16()? as u32;
+                let size = width as usize * height as usize * self.depth.bytes_per_pixel();
+                let pixels = if cel_type == CEL_RAW {
+                    chunk.bytes(size)?.to_vec()
+                } else {
+                    let mut pixels = vec![];
+                    ZlibDecoder::new(chunk.data)
+                        .take(size as u64)
+                        .read_to_end(&mut pixels)?;
+                    pixels
+                };
+                if pixels.len() < size {
+                    return Err(invalid_data("truncated Aseprite cel"));
+                }
+                Cel {
+                    layer,
+                    x,
+                    y,
+                    opacity,
+                    z_index,
+                    width,
+                    pixels,
+                }
+            }
+            CEL_LINKED => {
+                let linked = chunk.u16()? as usize;
+                let Some(cel) = self
+                    .frames
+                    .get(linked)
+                    .and_then(|frame| frame.cels.iter().find(|cel| cel.layer == layer))
+                else {
+                    return Ok(None);
+                };
+                Cel {
+                    opacity,
+                    z_index,
+                    ..cel.clone()
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(cel))
+    }
+
+    /// Returns the width of the image, in pixels.
+    pub const fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Returns the height of the image, in pixels.
+    pub const fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the number of frames in the file.
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns how long the given frame is shown for, or `None` if there is no such frame.
+    pub fn frame_duration(&self, frame: usize) -> Option<Duration> {
+        self.frames.get(frame).map(|frame| frame.duration)
+    }
+
+    /// Returns the names of the layers that can be drawn, from the bottom up. Group layers are skipped.
+    pub fn layer_names(&self) -> impl Iterator<Item = &str> {
+        self.layers
+            .iter()
+            .filter(|layer| !layer.group)
+            .map(|layer| layer.name.as_str())
+    }
+
+    /// Returns the tags of the file.
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    /// Returns the tag with the given name, if any.
+    pub fn tag(&self, name: &str) -> Option<&Tag> {
+        self.tags.iter().find(|tag| tag.name == name)
+    }
+
+    /// Converts a pixel of a cel to RGBA.
+    fn rgba(&self, pixel: &[u8], layer: &Layer) -> [u8; 4] {
+        match self.depth {
+            ColorDepth::Rgba => pixel.try_into().unwrap(),
+            ColorDepth::Grayscale => [pixel[0], pixel[0], pixel[0], pixel[1]],
+            ColorDepth::Indexed if pixel[0] == self.transparent_index && !layer.background => {
+                [0; 4]
+            }
+            ColorDepth::Indexed => self
+                .palette
+                .get(pixel[0] as usize)
+                .copied()
+                .unwrap_or([0; 4]),
+        }
+    }
+
+    /// Blends the cels of a frame into an image. Draws only the given layer if there is one,
+    /// and all visible layers otherwise.
+    fn frame_image(&self, frame: usize, layer: Option<&str>) -> Option<RgbaImage> {
+        let frame = self.frames.get(frame)?;
+        let layer = match layer {
+            Some(name) => Some(self.layers.iter().position(|layer| layer.name == name)?),
+            None => None,
+        };
+        let mut cels: Vec<&Cel> = frame
+            .cels
+            .iter()
+            .filter(|cel| match layer {
+                Some(layer) => cel.layer == layer,
+                None => self
+                    .layers
+                    .get(cel.layer)
+                    .is_some_and(|layer| layer.visible),
+            })
+            .collect();
+        // Cels are drawn in layer order, adjusted by their z-index
+        cels.sort_by_key(|cel| (cel.layer as i64 + cel.z_index as i64, cel.z_index));
+
+        let mut image = RgbaImage::new(self.width as u32, self.height as u32);
+        for cel in cels {
+            let Some(cel_layer) = self.layers.get(cel.layer) else {
+                continue;
+            };
+            let opacity = cel.opacity as u32 * cel_layer.opacity as u32 / 255;
+            let bytes = self.depth.bytes_per_pixel();
+            for (i, pixel) in cel.pixels.chunks_exact(bytes).enumerate() {
+                let x = cel.x + (i as u32 % cel.width) as i32;
+                let y = cel.y + (i as u32 / cel.width) as i32;
+                if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+                    continue;
+                }
+                let [r, g, b, a] = self.rgba(pixel, cel_layer);
+                let below = image.get_pixel_mut(x as u32, y as u32);
+                *below = image::Rgba(blend(below.0, [r, g, b], a as u32 * opacity / 255));
+            }
+        }
+        Some(image)
+    }
+
+    /// Reads a frame as a sprite. If a layer is given, only that layer is drawn. Otherwise,
+    /// all visible layers are blended together.
+    ///
+    /// Returns `None` if there is no such frame or layer.
+    pub fn sprite(
+        &self,
+        frame: usize,
+        layer: Option<&str>,
+        scale: u16,
+        priority: u16,
+    ) -> Option<Sprite> {
+        let image = self.frame_image(frame, layer)?;
+        Some(Sprite::from_image_data(
+            DynamicImage::ImageRgba8(image),
+            self.color_mode,
+            scale,
+            self.use_alpha_channel,
+            priority,
+        ))
+    }
+
+    /// Reads the frames with the given indices as an animation, keeping their durations.
+    fn frames_animation<I: IntoIterator<Item = usize>>(
+        &self,
+        frames: I,
+        layer: Option<&str>,
+        scale: u16,
+        priority: u16,
+        mode: LoopMode,
+    ) -> Option<AnimatedSprite> {
+        let mut animation = AnimatedSprite::new(mode);
+        for frame in frames {
+            animation.push_frame(
+                self.sprite(frame, layer, scale, priority)?,
+                self.frame_duration(frame)?,
+            );
+        }
+        Some(animation)
+    }
+
+    /// Reads all frames as a looping animation, keeping their durations. If a layer is given,
+    /// only that layer is drawn.
+    ///
+    /// Returns `None` if there is no such layer.
+    pub fn animation(
+        &self,
+        layer: Option<&str>,
+        scale: u16,
+        priority: u16,
+    ) -> Option<AnimatedSprite> {
+        self.frames_animation(0..self.frames.len(), layer, scale, priority, LoopMode::Loop)
+    }
+
+    /// Reads the frames of the tag with the given name as an animation, keeping their durations.
+    /// If a layer is given, only that layer is drawn.
+    ///
+    /// Frames are ordered by the direction of the tag. Ping-pong tags use [`LoopMode::PingPong`],
+    /// and other tags use [`LoopMode::Loop`].
+    ///
+    /// Returns `None` if there is no such tag or layer.
+    pub fn tag_animation(
+        &self,
+        tag: &str,
+        layer: Option<&str>,
+        scale: u16,
+        priority: u16,
+    ) -> Option<AnimatedSprite> {
+        let tag = self.tag(tag)?;
+        let frames: Vec<usize> = match tag.direction {
+            TagDirection::Forward | TagDirection::PingPong => (tag.from..=tag.to).collect(),
+            TagDirection::Reverse | TagDirection::PingPongReverse => {
+                (tag.from..=tag.to).rev().collect()
+            }
+        };
+        let mode = match tag.direction {
+            TagDirection::PingPong | TagDirection::PingPongReverse => LoopMode::PingPong,
+            TagDirection::Forward | TagDirection::Reverse => LoopMode::Loop,
+        };
+        self.frames_animation(frames, layer, scale, priority, mode)
+    }
+}
+
+/// Blends a color with the given alpha over a pixel.
+fn blend(below: [u8; 4], [r, g, b]: [u8; 3], alpha: u32) -> [u8; 4] {
+    let below_alpha = below[3] as u32 * (255 - alpha) / 255;
+    let out_alpha = alpha + below_alpha;
+    if out_alpha == 0 {
+        return [0; 4];
+    }
+    let channel = |above: u8, below: u8| {
+        ((above as u32 * alpha + below as u32 * below_alpha) / out_alpha) as u8
+    };
+    [
+        channel(r, below[0]),
+        channel(g, below[1]),
+        channel(b, below[2]),
+        out_alpha as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::ZlibEncoder, Compression};
+
+    use crate::color::Color;
+
+    use super::*;
+
+    fn chunk(kind: u16, body: &[u8]) -> Vec<u8> {
+        let mut data = (body.len() as u32 + 6).to_le_bytes().to_vec();
+        data.extend(kind.to_le_bytes());
+        data.extend(body);
+        data
+    }
+
+    fn frame(millis: u16, chunks: &[Vec<u8>]) -> Vec<u8> {
+        let body = chunks.concat();
+        let mut data = (body.len() as u32 + 16).to_le_bytes().to_vec();
+        data.extend(FRAME_MAGIC.to_le_bytes());
+        data.extend((chunks.len() as u16).to_le_bytes());
+        data.extend(millis.to_le_bytes());
+        data.extend([0; 6]);
+        data.extend(body);
+        data
+    }
+
+    fn file(depth: u16, width: u16, height: u16, frames: &[Vec<u8>]) -> Vec<u8> {
+        let body = frames.concat();
+        let mut data = (body.len() as u32 + 128).to_le_bytes().to_vec();
+        data.extend(FILE_MAGIC.to_le_bytes());
+        data.extend((frames.len() as u16).to_le_bytes());
+        data.extend(width.to_le_bytes());
+        data.extend(height.to_le_bytes());
+        data.extend(depth.to_le_bytes());
+        data.extend(HEADER_LAYER_OPACITY_VALID.to_le_bytes());
+        data.resize(128, 0);
+        data.extend(body);
+        data
+    }
+
+    fn string(s: &str) -> Vec<u8> {
+        let mut data = (s.len() as u16).to_le_bytes().to_vec();
+        data.extend(s.as_bytes());
+        data
+    }
+
+    fn layer(flags: u16, layer_type: u16, level: u16, name: &str) -> Vec<u8> {
+        let mut body = [flags, layer_type, level, 0, 0, 0]
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect::<Vec<u8>>();
+        body.extend([255, 0, 0, 0]);
+        body.extend(string(name));
+        chunk(LAYER_CHUNK, &body)
+    }
+
+    fn cel(layer: u16, x: i16, y: i16, cel_type: u16, data: &[u8]) -> Vec<u8> {
+        let mut body = layer.to_le_bytes().to_vec();
+        body.extend(x.to_le_bytes());
+        body.extend(y.to_le_bytes());
+        body.push(255);
+        body.extend(cel_type.to_le_bytes());
+        body.extend([0; 7]);
+        body.extend(data);
+        chunk(CEL_CHUNK, &body)
+    }
+
+    fn pixels(width: u16, height: u16, pixels: &[u8]) -> Vec<u8> {
+        let mut data = width.to_le_bytes().to_vec();
+        data.extend(height.to_le_bytes());
+        data.extend(pixels);
+        data
+    }
+
+    fn tags(tags: &[(u16, u16, u8, &str)]) -> Vec<u8> {
+        let mut body = (tags.len() as u16).to_le_bytes().to_vec();
+        body.extend([0; 8]);
+        for &(from, to, direction, name) in tags {
+            body.extend(from.to_le_bytes());
+            body.extend(to.to_le_bytes());
+            body.push(direction);
+            body.extend([0; 12]);
+            body.extend(string(name));
+        }
+        chunk(TAGS_CHUNK, &body)
+    }
+
+    fn sprite_cells(sprite: &Sprite) -> Vec<(char, Option<Color>)> {
+        sprite.offsets[0]
+            .iter()
+            .map(|colored| (colored.cell.to_braille_char(), colored.color))
+            .collect()
+    }
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const GREEN: [u8; 4] = [0, 255, 0, 255];
+
+    #[test]
+    fn rgba_layers() {
+        let mut compressed = ZlibEncoder::new(vec![], Compression::default());
+        compressed.write_all(&GREEN).unwrap();
+        let mut green = 1u16.to_le_bytes().repeat(2);
+        green.extend(compressed.finish().unwrap());
+
+        let data = file(
+            32,
+            4,
+            4,
+            &[
+                frame(
+                    100,
+                    &[
+                        layer(LAYER_VISIBLE, 0, 0, "body"),
+                        layer(0, 0, 0, "hidden"),
+                        layer(LAYER_VISIBLE, LAYER_GROUP, 0, "effects"),
+                        layer(LAYER_VISIBLE, 0, 1, "glow"),
+                        layer(0, LAYER_GROUP, 0, "disabled"),
+                        layer(LAYER_VISIBLE, 0, 1, "ghost"),
+                        cel(0, 0, 0, CEL_RAW, &pixels(2, 1, &[RED, RED].concat())),
+                        cel(1, 0, 3, CEL_RAW, &pixels(1, 1, &RED)),
+                        cel(3, 3, 0, CEL_COMPRESSED, &green),
+                        cel(5, 0, 1, CEL_RAW, &pixels(1, 1, &RED)),
+                        tags(&[(0, 1, 1, "back"), (0, 1, 2, "walk")]),
+                    ],
+                ),
+                frame(200, &[cel(0, 0, 0, CEL_LINKED, &0u16.to_le_bytes())]),
+            ],
+        );
+        let file = Aseprite::from_bytes(&data, ColorMode::TrueColor, true).unwrap();
+        assert_eq!((file.width(), file.height(), file.frame_count()), (4, 4, 2));
+        assert_eq!(
+            file.layer_names().collect::<Vec<_>>(),
+            ["body", "hidden", "glow", "ghost"]
+        );
+        assert_eq!(file.frame_duration(1), Some(Duration::from_millis(200)));
+        assert_eq!(file.tag("walk").unwrap().direction, TagDirection::PingPong);
+
+        let red = Some(Color::rgb(255, 0, 0));
+        let green = Some(Color::rgb(0, 255, 0));
+        let sprite = file.sprite(0, None, 1, 0).unwrap();
+        assert_eq!(sprite_cells(&sprite), [('⠉', red), ('⠈', green)]);
+        let sprite = file.sprite(0, Some("hidden"), 1, 0).unwrap();
+        assert_eq!(sprite_cells(&sprite)[0], ('⡀', red));
+        let sprite = file.sprite(1, None, 1, 0).unwrap();
+        assert_eq!(sprite_cells(&sprite)[..1], [('⠉', red)]);
+        assert!(file.sprite(0, Some("missing"), 1, 0).is_none());
+        assert!(file.sprite(2, None, 1, 0).is_none());
+
+        let back = file.tag_animation("back", Some("glow"), 1, 0).unwrap();
+        let durations: Vec<_> = back
+            .frames()
+            .iter()
+            .map(|(_, duration)| *duration)
+            .collect();
+        assert_eq!(
+            durations,
+            [Duration::from_millis(200), Duration::from_millis(100)]
+        );
+        assert_eq!(back.mode, LoopMode::Loop);
+        assert_eq!(sprite_cells(&back.frames()[1].0)[1], ('⠈', green));
+        let walk = file.tag_animation("walk", None, 1, 0).unwrap();
+        assert_eq!(walk.mode, LoopMode::PingPong);
+        assert_eq!(file.animation(None, 1, 0).unwrap().len(), 2);
+        assert!(file.tag_animation("run", None, 1, 0).is_none());
+    }
+
+    #[test]
+    fn indexed_palette() {
+        let mut palette = 2u32.to_le_bytes().to_vec();
+        palette.extend([0; 4]);
+        palette.extend(1u32.to_le_bytes());
+        palette.extend([0; 8]);
+        palette.extend([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 255]);
+        let data = file(
+            8,
+            2,
+            4,
+            &[frame(
+                100,
+                &[
+                    chunk(PALETTE_CHUNK, &palette),
+                    layer(LAYER_VISIBLE, 0, 0, "layer"),
+                    cel(0, 0, 0, CEL_RAW, &pixels(2, 4, &[1, 0, 0, 0, 0, 0, 0, 1])),
+                ],
+            )],
+        );
+        let file = Aseprite::from_bytes(&data, ColorMode::TrueColor, true).unwrap();
+        let sprite = file.sprite(0, None, 1, 0).unwrap();
+        assert_eq!(sprite_cells(&sprite), [('⢁', Some(Color::rgb(0, 0, 255)))]);
+    }
+
+    #[test]
+    fn invalid_files() {
+        let mut wrong_depth = file(32, 2, 4, &[]);
+        wrong_depth[12] = 24;
+        let mut truncated = file(32, 2, 4, &[frame(100, &[layer(LAYER_VISIBLE, 0, 0, "a")])]);
+        truncated.truncate(150);
+        let mut wrong_magic = file(32, 2, 4, &[]);
+        wrong_magic[4] = 0;
+        // The cels claim far more pixels than they contain
+        let mut compressed = ZlibEncoder::new(vec![], Compression::default());
+        compressed.write_all(&GREEN).unwrap();
+        let mut short_pixels = u16::MAX.to_le_bytes().repeat(2);
+        short_pixels.extend(compressed.finish().unwrap());
+        let [truncated_raw, truncated_compressed] = [
+            cel(0, 0, 0, CEL_RAW, &pixels(u16::MAX, u16::MAX, &GREEN)),
+            cel(0, 0, 0, CEL_COMPRESSED, &short_pixels),
+        ]
+        .map(|cel| {
+            file(
+                32,
+                2,
+                4,
+                &[frame(100, &[layer(LAYER_VISIBLE, 0, 0, "a"), cel])],
+            )
+        });
+        // Indexed pixels can't use more than 256 colors
+        let mut palette = u32::MAX.to_le_bytes().to_vec();
+        palette.extend([0; 16]);
+        palette.extend([0; 6]);
+        // Skips 255 entries, then sets 256 more
+        let mut old_palette = vec![1, 0, 255, 0];
+        old_palette.extend([0; 256 * 3]);
+        let large_palettes = [
+            chunk(PALETTE_CHUNK, &palette),
+            chunk(OLD_PALETTE_CHUNK, &old_palette),
+        ];
+        let [large_palette, large_old_palette] = large_palettes
+            .clone()
+            .map(|palette| file(8, 2, 4, &[frame(100, &[palette])]));
+        for data in [
+            vec![],
+            wrong_depth,
+            truncated,
+            wrong_magic,
+            truncated_raw,
+            truncated_compressed,
+            large_palette,
+            large_old_palette,
+        ] {
+            let error = Aseprite::from_bytes(&data, ColorMode::TrueColor, true).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+        // Other color depths don't use the palette
+        for palette in large_palettes {
+            let data = file(32, 2, 4, &[frame(100, &[palette])]);
+            assert!(Aseprite::from_bytes(&data, ColorMode::TrueColor, true).is_ok());
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! Module for testing whether sprites overlap, pixel by pixel.
+
+use std::ops::Range;
+
+use super::*;
+
+use crate::{
+    screen::Screen,
+    units::{offset_px, signed_pos_components},
+};
+
+/// A rectangle of pixels, such as the area where two sprites overlap. The position may be negative.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    /// The x position of the left edge, in pixels
+    pub x: i32,
+    /// The y position of the top edge, in pixels
+    pub y: i32,
+    /// The width in pixels
+    pub width: u32,
+    /// The height in pixels
+    pub height: u32,
+}
+
+/// The cells of a sprite drawn at a pixel position, i.e. its data at the matching pixel offset.
+struct Placed<'a> {
+    data: &'a SpriteData,
+    /// The cell position of the top left corner
+    x: i32,
+    y: i32,
+    width: u16,
+    height: u16,
+}
+
+impl<'a> Placed<'a> {
+    fn new(sprite: &'a Sprite, x_pixel: i32, y_pixel: i32) -> Self {
+        let ((x, x_px), (y, y_px)) = signed_pos_components(x_pixel, y_pixel);
+        let offset = px_offset(x_px, y_px);
+        let (width, height) = sprite.offset_size(offset);
+        Self {
+            data: &sprite.offsets[offset as usize],
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn columns(&self) -> Range<i32> {
+        self.x..self.x + self.width as i32
+    }
+
+    fn rows(&self) -> Range<i32> {
+        self.y..self.y + self.height as i32
+    }
+
+    /// Returns the pixels of the cell at the given cell position, which must be within the sprite.
+    fn bits(&self, x: i32, y: i32) -> u8 {
+        let (x, y) = ((x - self.x) as u16, (y - self.y) as u16);
+        self.data[index(x, y, self.width)].cell.bits
+    }
+}
+
+fn intersect(a: Range<i32>, b: Range<i32>) -> Range<i32> {
+    a.start.max(b.start)..a.end.min(b.end)
+}
+
+/// Iterates over the cells where a sprite overlaps the pixels given by `bits`, within the given
+/// ranges of cell positions. Yields the cell positions and the overlapping pixels of each cell.
+fn overlapping_cells<'a, F: Fn(i32, i32) -> u8 + 'a>(
+    placed: &'a Placed,
+    columns: Range<i32>,
+    rows: Range<i32>,
+    bits: F,
+) -> impl Iterator<Item = (i32, i32, u8)> + 'a {
+    rows.flat_map(move |y| columns.clone().map(move |x| (x, y)))
+        .filter_map(move |(x, y)| {
+            let overlap = placed.bits(x, y) & bits(x, y);
+            (overlap != 0).then_some((x, y, overlap))
+        })
+}
+
+/// Computes the smallest rectangle containing the pixels of the given cells.
+fn bounding_rect<I: Iterator<Item = (i32, i32, u8)>>(cells: I) -> Option<Rect> {
+    let mut bounds: Option<(i32, i32, i32, i32)> = None;
+    for (x, y, bits) in cells {
+        for bit in (0..8).filter(|bit| bits & (1 << bit) != 0) {
+            let (x_px, y_px) = offset_px(bit);
+            let x = x * PIXEL_WIDTH as i32 + x_px as i32;
+            let y = y * PIXEL_HEIGHT as i32 + y_px as i32;
+            bounds = Some(match bounds {
+                Some((left, top, right, bottom)) => {
+                    (left.min(x), top.min(y), right.max(x), bottom.max(y))
+                }
+                None => (x, y, x, y),
+            });
+        }
+    }
+    bounds.map(|(left, top, right, bottom)| Rect {
+        x: left,
+        y: top,
+        width: (right - left + 1) as u32,
+        height: (bottom - top + 1) as u32,
+    })
+}
+
+/// Iterates over the cells where two placed sprites overlap. The cells are compared a whole cell
+/// at a time, using the precomputed offsets of the sprites.
+fn overlapping_sprites<'a>(
+    a: &'a Placed<'a>,
+    b: &'a Placed<'a>,
+) -> impl Iterator<Item = (i32, i32, u8)> + 'a {
+    let columns = intersect(a.columns(), b.columns());
+    let rows = intersect(a.rows(), b.rows());
+    overlapping_cells(a, columns, rows, |x, y| b.bits(x, y))
+}
+
+impl Sprite {
+    /// Returns `true` if any set pixel of this sprite overlaps a set pixel of another sprite,
+    /// when the sprites are drawn at the given pixel positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::sprite::Sprite;
+    ///
+    /// let dot = Sprite::from_braille_string(&["⠁"], None, 0).unwrap();
+    /// let ring = Sprite::from_braille_string(&["⢎⡱"], None, 0).unwrap();
+    /// // The dot is inside the ring, but doesn't touch it
+    /// assert!(!ring.collides(0, 0, &dot, 2, 1));
+    /// assert!(ring.collides(0, 0, &dot, 2, 0));
+    /// ```
+    pub fn collides(&self, x: i32, y: i32, other: &Sprite, other_x: i32, other_y: i32) -> bool {
+        let (a, b) = (
+            Placed::new(self, x, y),
+            Placed::new(other, other_x, other_y),
+        );
+        let mut cells = overlapping_sprites(&a, &b);
+        cells.next().is_some()
+    }
+
+    /// Computes the smallest rectangle containing the pixels where this sprite and another sprite
+    /// overlap, when drawn at the given pixel positions. Returns `None` if they don't overlap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::sprite::{Rect, Sprite};
+    ///
+    /// let block = Sprite::rectangle(4, 4, None, 0);
+    /// let overlap = block.overlap(0, 0, &block, 3, -2);
+    /// assert_eq!(overlap, Some(Rect { x: 3, y: 0, width: 1, height: 2 }));
+    /// ```
+    pub fn overlap(
+        &self,
+        x: i32,
+        y: i32,
+        other: &Sprite,
+        other_x: i32,
+        other_y: i32,
+    ) -> Option<Rect> {
+        let (a, b) = (
+            Placed::new(self, x, y),
+            Placed::new(other, other_x, other_y),
+        );
+        bounding_rect(overlapping_sprites(&a, &b))
+    }
+}
+
+impl Screen {
+    /// Iterates over the cells where a sprite drawn at the given pixel position overlaps
+    /// the pixels on the screen.
+    fn overlapping_screen<'a>(
+        &'a self,
+        placed: &'a Placed,
+    ) -> impl Iterator<Item = (i32, i32, u8)> + 'a {
+        let columns = intersect(placed.columns(), 0..self.width() as i32);
+        let rows = intersect(placed.rows(), 0..self.height() as i32);
+        overlapping_cells(placed, columns, rows, |x, y| {
+            self.get_cell(x as u16, y as u16).unwrap_or_default().bits
+        })
+    }
+
+    /// Returns `true` if any set pixel of a sprite drawn at the given pixel position would overlap
+    /// a pixel that is already set on the screen. Parts of the sprite outside of the screen never collide.
+    ///
+    /// Only the pixels of the screen are compared, not text drawn with [`Screen::draw_char()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::screen::{Blit, Screen};
+    /// use ti::sprite::Sprite;
+    ///
+    /// let mut screen = Screen::new_pixels(8, 4);
+    /// screen.draw_pixel(5, 3, Blit::Set);
+    /// let block = Sprite::rectangle(2, 2, None, 0);
+    /// assert!(!screen.collides(&block, 4, 1));
+    /// assert!(screen.collides(&block, 4, 2));
+    /// ```
+    pub fn collides(&self, sprite: &Sprite, x_pixel: i32, y_pixel: i32) -> bool {
+        let placed = Placed::new(sprite, x_pixel, y_pixel);
+        let mut cells = self.overlapping_screen(&placed);
+        cells.next().is_some()
+    }
+
+    /// Computes the smallest rectangle containing the set pixels of the screen that a sprite drawn at
+    /// the given pixel position would overlap. Returns `None` if there are none. See [`Screen::collides()`].
+    pub fn overlap(&self, sprite: &Sprite, x_pixel: i32, y_pixel: i32) -> Option<Rect> {
+        let placed = Placed::new(sprite, x_pixel, y_pixel);
+        bounding_rect(self.overlapping_screen(&placed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::screen::Blit;
+
+    use super::*;
+
+    #[test]
+    fn sprite_collisions() {
+        // A rectangle outline, 4 pixels wide and 5 tall
+        let frame = Sprite::from_braille_string(&["⡏⢹", "⠉⠉"], None, 0).unwrap();
+        let dot = Sprite::from_braille_string(&["⠁"], None, 0).unwrap();
+        for (x, y, expected) in [
+            (
+                0,
+                0,
+                Some(Rect {
+                    x: 0,
+                    y: 0,
+                    width: 1,
+                    height: 1,
+                }),
+            ),
+            (1, 1, None),
+            (2, 2, None),
+            (
+                3,
+                2,
+                Some(Rect {
+                    x: 3,
+                    y: 2,
+                    width: 1,
+                    height: 1,
+                }),
+            ),
+            (
+                1,
+                4,
+                Some(Rect {
+                    x: 1,
+                    y: 4,
+                    width: 1,
+                    height: 1,
+                }),
+            ),
+            (4, 0, None),
+            (-1, 0, None),
+        ] {
+            assert_eq!(frame.overlap(0, 0, &dot, x, y), expected, "dot at {x}, {y}");
+            assert_eq!(dot.collides(x, y, &frame, 0, 0), expected.is_some());
+        }
+
+        // Negative positions are rounded down to whole cells
+        assert_eq!(
+            frame.overlap(-3, -5, &frame, -5, -2),
+            Some(Rect {
+                x: -3,
+                y: -2,
+                width: 2,
+                height: 2
+            })
+        );
+        let block = Sprite::rectangle(3, 3, None, 0);
+        assert_eq!(
+            block.overlap(0, 0, &block, 1, 1),
+            Some(Rect {
+                x: 1,
+                y: 1,
+                width: 2,
+                height: 2
+            })
+        );
+        assert!(!block.collides(0, 0, &block, 3, 0));
+        assert!(!block.collides(0, 0, &Sprite::empty(2, 2, 0), 0, 0));
+    }
+
+    #[test]
+    fn screen_collisions() {
+        let mut screen = Screen::new_pixels(6, 8);
+        screen.draw_pixel(0, 0, Blit::Set);
+        screen.draw_pixel(5, 7, Blit::Set);
+        screen.draw_pixel(3, 4, Blit::Set);
+        let block = Sprite::rectangle(4, 4, None, 0);
+        assert_eq!(
+            screen.overlap(&block, -3, -3),
+            Some(Rect {
+                x: 0,
+                y: 0,
+                width: 1,
+                height: 1
+            })
+        );
+        assert_eq!(
+            screen.overlap(&block, 2, 4),
+            Some(Rect {
+                x: 3,
+                y: 4,
+                width: 3,
+                height: 4
+            })
+        );
+        assert!(!screen.collides(&block, 1, 0));
+        assert!(!screen.collides(&block, 6, 4));
+    }
+}
@@ -2,31 +2,163 @@
 
 use super::*;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::BufReader;
 use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
 
 pub use image::ImageResult;
 
+use image::codecs::{gif::GifDecoder, png::PngDecoder};
 use image::imageops::FilterType::Nearest;
-use image::{DynamicImage, GenericImage, GenericImageView, Rgba};
+use image::{
+    AnimationDecoder, DynamicImage, Frames, GenericImage, GenericImageView, ImageFormat, Rgba,
+};
 
 use crate::units::pos_components;
 
 /// The different ways that raw pixel data can be interpreted as a sprite.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ColorMode {
+    /// Colors are ignored.
     Monochrome,
+    /// Colors are approximated by the 16 ANSI standard colors.
     Standard,
+    /// Colors are approximated by the 256 ANSI colors.
     Rgb,
+    /// Colors are kept exactly, as 24-bit RGB colors.
+    TrueColor,
+}
+
+/// A grid of equally sized tiles in an [`Atlas`].
+///
+/// The first tile starts `margin` pixels from the top left corner of the image,
+/// and neighbouring tiles are separated by `spacing` pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "manifest", derive(serde::Deserialize))]
+pub struct Grid {
+    /// The width of each tile, in pixels.
+    pub tile_width: u32,
+    /// The height of each tile, in pixels.
+    pub tile_height: u32,
+    /// The space around the tiles, in pixels.
+    #[cfg_attr(feature = "manifest", serde(default))]
+    pub margin: u32,
+    /// The space between tiles, in pixels.
+    #[cfg_attr(feature = "manifest", serde(default))]
+    pub spacing: u32,
+}
+
+impl Grid {
+    /// Creates a grid of tiles of the given size, without margin or spacing.
+    pub const fn new(tile_width: u32, tile_height: u32) -> Self {
+        Self {
+            tile_width,
+            tile_height,
+            margin: 0,
+            spacing: 0,
+        }
+    }
+
+    /// Returns a copy of the grid with the given margin around the tiles.
+    pub const fn with_margin(self, margin: u32) -> Self {
+        Self { margin, ..self }
+    }
+
+    /// Returns a copy of the grid with the given spacing between tiles.
+    pub const fn with_spacing(self, spacing: u32) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Returns the region covered by the tile in the given column and row. Returns `None` if
+    /// its position doesn't fit in a `u32`.
+    pub fn tile(&self, col: u32, row: u32) -> Option<Region> {
+        let offset = |index: u32, tile: u32| {
+            let stride = tile.checked_add(self.spacing)?;
+            index.checked_mul(stride)?.checked_add(self.margin)
+        };
+        Some(Region {
+            x: offset(col, self.tile_width)?,
+            y: offset(row, self.tile_height)?,
+            width: self.tile_width,
+            height: self.tile_height,
+        })
+    }
+
+    /// Returns the number of columns and rows of whole tiles that fit in an image of the given size.
+    pub fn size(&self, width: u32, height: u32) -> (u32, u32) {
+        let count = |length: u32, tile: u32| {
+            // The last tile isn't followed by spacing
+            let margins = self.margin.checked_mul(2)?;
+            let available = length.saturating_sub(margins).checked_add(self.spacing)?;
+            available.checked_div(tile.checked_add(self.spacing)?)
+        };
+        (
+            count(width, self.tile_width).unwrap_or(0),
+            count(height, self.tile_height).unwrap_or(0),
+        )
+    }
+}
+
+/// A rectangular region of an [`Atlas`], in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "manifest", derive(serde::Deserialize))]
+pub struct Region {
+    /// The left edge of the region.
+    pub x: u32,
+    /// The top edge of the region.
+    pub y: u32,
+    /// The width of the region.
+    pub width: u32,
+    /// The height of the region.
+    pub height: u32,
+}
+
+/// Everything that determines how a sprite is read from an [`Atlas`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct SpriteKey {
+    region: Region,
+    color_mode: ColorMode,
+    use_alpha_channel: bool,
+    scale: u16,
+    priority: u16,
 }
 
 /// A sprite atlas opened from a file.
+///
+/// Sprites can be fetched by their pixel coordinates, by their position in a [`Grid`] of tiles,
+/// or by the name of a [`Region`]. Sprites are cached, so fetching the same sprite again
+/// returns the same [`Arc`] without parsing the image again. The cache is shared between threads.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use ti::sprite::{Atlas, ColorMode, Grid, Region};
+///
+/// let mut atlas = Atlas::open("examples/heart.png", ColorMode::TrueColor, true)
+///     .unwrap()
+///     .with_grid(Grid::new(8, 8));
+/// assert_eq!(atlas.grid_size(), (2, 2));
+/// let tile = atlas.tile(1, 0, 1, 0).unwrap();
+/// assert!(Arc::ptr_eq(&tile, &atlas.tile_index(1, 1, 0).unwrap()));
+/// assert!(atlas.tile(2, 0, 1, 0).is_none());
+///
+/// atlas.insert_region("heart", Region { x: 0, y: 0, width: 16, height: 16 });
+/// assert_eq!(atlas.named("heart", 1, 0).unwrap().default_width(), 8);
+/// ```
 pub struct Atlas {
     image: DynamicImage,
     /// A setting to determine how sprites are read from this atlas
     pub color_mode: ColorMode,
     /// A setting to determine how sprites are read from this atlas
     pub use_alpha_channel: bool,
+    grid: Option<Grid>,
+    regions: HashMap<String, Region>,
+    cache: Mutex<HashMap<SpriteKey, Arc<Sprite>>>,
 }
 
 impl Atlas {
@@ -40,9 +172,33 @@ impl Atlas {
             image,
             color_mode,
             use_alpha_channel,
+            grid: None,
+            regions: HashMap::new(),
+            cache: Mutex::new(HashMap::new()),
         })
     }
+
+    /// Returns the size of the atlas image, in pixels.
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.image.dimensions()
+    }
+
+    /// Returns whether a region is entirely within the atlas image. Empty regions are never contained.
+    pub fn contains(&self, region: Region) -> bool {
+        let (width, height) = self.dimensions();
+        let within = |start: u32, length: u32, end: u32| {
+            length > 0 && start.checked_add(length).is_some_and(|last| last <= end)
+        };
+        within(region.x, region.width, width) && within(region.y, region.height, height)
+    }
+
     /// Fetches the sprite at the given coordinates in this atlas.
+    ///
+    /// This returns a copy of the cached sprite. Use [`Atlas::cached_sprite()`] to share it instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region isn't entirely within the atlas image. See [`Atlas::contains()`].
     pub fn sprite(
         &self,
         x: u32,
@@ -52,13 +208,215 @@ impl Atlas {
         scale: u16,
         priority: u16,
     ) -> Sprite {
-        Sprite::from_image_data(
-            DynamicImage::ImageRgba8(self.image.view(x, y, width, height).to_image()),
-            self.color_mode,
+        let region = Region {
+            x,
+            y,
+            width,
+            height,
+        };
+        let sprite = self.cached_sprite(region, scale, priority);
+        Sprite::clone(&sprite.expect("the region is outside of the atlas"))
+    }
+
+    /// Fetches the sprite in the given region of this atlas, parsing it only the first time
+    /// it is requested with the same settings. Returns `None` if the region isn't entirely
+    /// within the atlas image.
+    pub fn cached_sprite(&self, region: Region, scale: u16, priority: u16) -> Option<Arc<Sprite>> {
+        if !self.contains(region) {
+            return None;
+        }
+        let key = SpriteKey {
+            region,
+            color_mode: self.color_mode,
+            use_alpha_channel: self.use_alpha_channel,
             scale,
-            self.use_alpha_channel,
             priority,
-        )
+        };
+        // The cache stays consistent even if parsing a sprite panicked
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        let sprite = cache.entry(key).or_insert_with(|| {
+            let Region {
+                x,
+                y,
+                width,
+                height,
+            } = region;
+            Arc::new(Sprite::from_image_data(
+                DynamicImage::ImageRgba8(self.image.view(x, y, width, height).to_image()),
+                self.color_mode,
+                scale,
+                self.use_alpha_channel,
+                priority,
+            ))
+        });
+        Some(Arc::clone(sprite))
+    }
+
+    /// Removes all cached sprites.
+    pub fn clear_cache(&mut self) {
+        self.cache
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Returns the atlas with the given grid of tiles, for use with [`Atlas::tile()`].
+    pub fn with_grid(mut self, grid: Grid) -> Self {
+        self.set_grid(Some(grid));
+        self
+    }
+
+    /// Sets the grid of tiles of the atlas.
+    pub fn set_grid(&mut self, grid: Option<Grid>) {
+        self.grid = grid;
+    }
+
+    /// Returns the grid of tiles of the atlas, if any.
+    pub const fn grid(&self) -> Option<Grid> {
+        self.grid
+    }
+
+    /// Returns the number of columns and rows of tiles in the atlas, or `(0, 0)` without a grid.
+    pub fn grid_size(&self) -> (u32, u32) {
+        let (width, height) = self.dimensions();
+        self.grid.map_or((0, 0), |grid| grid.size(width, height))
+    }
+
+    /// Fetches the tile in the given column and row of the grid. Returns `None` if the atlas
+    /// has no grid, or the tile is outside of the image.
+    pub fn tile(&self, col: u32, row: u32, scale: u16, priority: u16) -> Option<Arc<Sprite>> {
+        let (cols, rows) = self.grid_size();
+        let grid = self.grid.filter(|_| col < cols && row < rows)?;
+        self.cached_sprite(grid.tile(col, row)?, scale, priority)
+    }
+
+    /// Fetches the tile with the given index in the grid, counting left to right and then
+    /// top to bottom. Returns `None` if the atlas has no grid, or the tile is outside of the image.
+    pub fn tile_index(&self, index: u32, scale: u16, priority: u16) -> Option<Arc<Sprite>> {
+        let (cols, _) = self.grid_size();
+        if cols == 0 {
+            return None;
+        }
+        self.tile(index % cols, index / cols, scale, priority)
+    }
+
+    /// Names a region of the atlas, for use with [`Atlas::named()`]. Replaces any region
+    /// previously given the same name.
+    pub fn insert_region<S: Into<String>>(&mut self, name: S, region: Region) {
+        self.regions.insert(name.into(), region);
+    }
+
+    /// Returns the region with the given name, if any.
+    pub fn region(&self, name: &str) -> Option<Region> {
+        self.regions.get(name).copied()
+    }
+
+    /// Fetches the sprite in the region with the given name. Returns `None` if there is no such region,
+    /// or it isn't entirely within the atlas image.
+    pub fn named(&self, name: &str, scale: u16, priority: u16) -> Option<Arc<Sprite>> {
+        let region = self.region(name)?;
+        self.cached_sprite(region, scale, priority)
+    }
+
+    /// Fetches an animation from a horizontal strip of `frame_count` equally sized frames in this atlas,
+    /// starting at the given coordinates. Each frame is shown for `frame_duration`, in a loop.
+    /// Returns `None` if any of the frames isn't entirely within the atlas image.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use ti::sprite::{Atlas, ColorMode};
+    ///
+    /// let atlas = Atlas::open("examples/heart.png", ColorMode::TrueColor, true).unwrap();
+    /// let animation = atlas.animation(0, 0, 8, 8, 2, Duration::from_millis(100), 1, 0);
+    /// assert_eq!(animation.unwrap().len(), 2);
+    /// assert!(atlas.animation(0, 0, 8, 8, 3, Duration::from_millis(100), 1, 0).is_none());
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn animation(
+        &self,
+        x: u32,
+        y: u32,
+        frame_width: u32,
+        frame_height: u32,
+        frame_count: u32,
+        frame_duration: Duration,
+        scale: u16,
+        priority: u16,
+    ) -> Option<AnimatedSprite> {
+        let frames = (0..frame_count)
+            .map(|i| {
+                let region = Region {
+                    x: i.checked_mul(frame_width)?.checked_add(x)?,
+                    y,
+                    width: frame_width,
+                    height: frame_height,
+                };
+                let sprite = self.cached_sprite(region, scale, priority)?;
+                Some(Sprite::clone(&sprite))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(AnimatedSprite::from_frames(
+            frames,
+            frame_duration,
+            LoopMode::Loop,
+        ))
+    }
+}
+
+impl AnimatedSprite {
+    /// Reads an animation from an animated GIF or PNG (APNG) file, keeping the delays of its frames.
+    /// The animation loops.
+    ///
+    /// Other images, including PNG files that are not animated, are read as a single frame
+    /// with a zero duration.
+    ///
+    /// The frames are parsed like in [`Atlas::sprite()`].
+    pub fn from_animation_path<P: AsRef<Path>>(
+        path: P,
+        color_mode: ColorMode,
+        scale: u16,
+        use_alpha_channel: bool,
+        priority: u16,
+    ) -> ImageResult<Self> {
+        let path = path.as_ref();
+        let frames: Option<Frames> = match ImageFormat::from_path(path)? {
+            ImageFormat::Gif => {
+                Some(GifDecoder::new(BufReader::new(File::open(path)?))?.into_frames())
+            }
+            ImageFormat::Png => {
+                let decoder = PngDecoder::new(BufReader::new(File::open(path)?))?;
+                if decoder.is_apng() {
+                    Some(decoder.apng().into_frames())
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        let Some(frames) = frames else {
+            let sprite = Sprite::from_image_data(
+                image::open(path)?,
+                color_mode,
+                scale,
+                use_alpha_channel,
+                priority,
+            );
+            return Ok(Self::from_frames([sprite], Duration::ZERO, LoopMode::Loop));
+        };
+        let mut animation = Self::new(LoopMode::Loop);
+        for frame in frames {
+            let frame = frame?;
+            let duration = Duration::from(frame.delay());
+            let image = DynamicImage::ImageRgba8(frame.into_buffer());
+            animation.push_frame(
+                Sprite::from_image_data(image, color_mode, scale, use_alpha_channel, priority),
+                duration,
+            );
+        }
+        Ok(animation)
     }
 }
 
@@ -88,6 +446,25 @@ impl Sprite {
         ))
     }
 
+    /// Reads and parses an image sprite from the specified file path using exact 24-bit colors.
+    ///
+    /// This is a version of [`Sprite::rgb_from_image_path()`] that keeps the colors of the image exactly,
+    /// instead of approximating them with ANSI colors.
+    pub fn truecolor_from_image_path<P: AsRef<std::path::Path>>(
+        path: P,
+        scale: u16,
+        use_alpha_channel: bool,
+        priority: u16,
+    ) -> image::ImageResult<Self> {
+        Ok(Self::from_image_data(
+            image::open(path)?,
+            ColorMode::TrueColor,
+            scale,
+            use_alpha_channel,
+            priority,
+        ))
+    }
+
     /// Reads and parses an image sprite from the specified file path using standard ANSI colors.
     ///
     /// This is a version of [`Sprite::rgb_from_image_path()`] that parses colors as standard colors only.
@@ -129,7 +506,7 @@ impl Sprite {
     /// the method used to thumbnail each cell into a single color.
     /// `color_mode` specifies the color resolution used in the output, and `use_alpha_channel` dictates whether the image's alpha channel
     /// will be used to infer sprite shape.
-    fn from_image_data(
+    pub(super) fn from_image_data(
         mut img: DynamicImage,
         color_mode: ColorMode,
         scale: u16,
@@ -165,7 +542,7 @@ impl Sprite {
         }
 
         // Then, pixel colors
-        if matches!(color_mode, ColorMode::Rgb | ColorMode::Standard) {
+        if color_mode != ColorMode::Monochrome {
             for y_cell in 0..height_cells {
                 for x_cell in 0..width_cells {
                     let x_px = x_cell * PIXEL_WIDTH as u16;
@@ -184,10 +561,10 @@ impl Sprite {
                     let mut pxs = BTreeMap::new();
                     for (_, _, Rgba([r, g, b, a])) in view.pixels() {
                         if a > 128 || !use_alpha_channel {
-                            let color = if matches!(color_mode, ColorMode::Rgb) {
-                                Color::from_rgb_approximate(r, g, b)
-                            } else {
-                                Color::standard_color_approximate(r, g, b)
+                            let color = match color_mode {
+                                ColorMode::TrueColor => Color::rgb(r, g, b),
+                                ColorMode::Rgb => Color::from_rgb_approximate(r, g, b),
+                                _ => Color::standard_color_approximate(r, g, b),
                             };
                             pxs.entry(color).and_modify(|n| *n += 1).or_insert(1);
                         }
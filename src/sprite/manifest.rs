@@ -0,0 +1,280 @@
+//! Module for naming the regions of an atlas with a manifest file.
+
+use super::*;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use image::{ImageError, ImageResult};
+use serde::Deserialize;
+
+/// The file formats that an atlas manifest can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestFormat {
+    /// [TOML](https://toml.io), for files ending in `.toml`.
+    Toml,
+    /// JSON, for files ending in `.json`.
+    Json,
+}
+
+impl ManifestFormat {
+    /// Guesses the format of a manifest from the extension of its path.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?;
+        if extension.eq_ignore_ascii_case("toml") {
+            Some(Self::Toml)
+        } else if extension.eq_ignore_ascii_case("json") {
+            Some(Self::Json)
+        } else {
+            None
+        }
+    }
+}
+
+/// A region of a manifest, given either in pixels or in tiles of the grid.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ManifestRegion {
+    Pixels(Region),
+    Tiles {
+        col: u32,
+        row: u32,
+        #[serde(default = "one")]
+        cols: u32,
+        #[serde(default = "one")]
+        rows: u32,
+    },
+}
+
+const fn one() -> u32 {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    image: Option<PathBuf>,
+    grid: Option<Grid>,
+    #[serde(default)]
+    regions: HashMap<String, ManifestRegion>,
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+impl Manifest {
+    fn parse(text: &str, format: ManifestFormat) -> io::Result<Self> {
+        match format {
+            ManifestFormat::Toml => toml::from_str(text).map_err(invalid_data),
+            ManifestFormat::Json => serde_json::from_str(text).map_err(invalid_data),
+        }
+    }
+}
+
+impl Atlas {
+    /// Opens a sprite atlas described by a manifest file, in TOML or JSON depending on its extension.
+    ///
+    /// The manifest names the atlas image, relative to the manifest's directory. It can also declare
+    /// a [`Grid`] of tiles and name regions of the image; see [`Atlas::load_manifest()`].
+    ///
+    /// ```toml
+    /// image = "heroes.png"
+    ///
+    /// [grid]
+    /// tile_width = 16
+    /// tile_height = 16
+    ///
+    /// [regions]
+    /// knight = { col = 0, row = 0 }
+    /// castle = { x = 32, y = 0, width = 48, height = 32 }
+    /// ```
+    pub fn open_manifest<P: AsRef<Path>>(
+        path: P,
+        color_mode: ColorMode,
+        use_alpha_channel: bool,
+    ) -> ImageResult<Self> {
+        let path = path.as_ref();
+        let format = ManifestFormat::from_path(path)
+            .ok_or_else(|| invalid_data("unknown manifest format, expected .toml or .json"))?;
+        let manifest = Manifest::parse(&fs::read_to_string(path)?, format)?;
+        let image = manifest
+            .image
+            .as_ref()
+            .ok_or_else(|| invalid_data("the manifest doesn't name an image"))?;
+        let image = path.parent().unwrap_or(Path::new("")).join(image);
+        let mut atlas = Self::open(image, color_mode, use_alpha_channel)?;
+        atlas
+            .apply_manifest(manifest)
+            .map_err(ImageError::IoError)?;
+        Ok(atlas)
+    }
+
+    /// Sets the grid and adds the named regions declared in a manifest, replacing regions with
+    /// the same names. Any image named by the manifest is ignored.
+    ///
+    /// Regions are given either in pixels, with `x`, `y`, `width` and `height`, or in tiles of
+    /// the grid, with `col` and `row`, and optionally `cols` and `rows` for regions spanning
+    /// multiple tiles.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if the manifest is invalid,
+    /// has regions in tiles but no grid, or has regions that are empty or aren't entirely within the image.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::sprite::{Atlas, ColorMode, ManifestFormat, Region};
+    ///
+    /// let mut atlas = Atlas::open("examples/heart.png", ColorMode::TrueColor, true).unwrap();
+    /// let manifest = r#"{
+    ///     "grid": { "tile_width": 8, "tile_height": 8 },
+    ///     "regions": { "top": { "col": 0, "row": 0, "cols": 2 } }
+    /// }"#;
+    /// atlas.load_manifest(manifest, ManifestFormat::Json).unwrap();
+    /// assert_eq!(atlas.region("top"), Some(Region { x: 0, y: 0, width: 16, height: 8 }));
+    /// ```
+    pub fn load_manifest(&mut self, text: &str, format: ManifestFormat) -> io::Result<()> {
+        self.apply_manifest(Manifest::parse(text, format)?)
+    }
+
+    fn apply_manifest(&mut self, manifest: Manifest) -> io::Result<()> {
+        let grid = manifest.grid.or(self.grid());
+        let mut regions = Vec::with_capacity(manifest.regions.len());
+        for (name, region) in manifest.regions {
+            let region = match region {
+                ManifestRegion::Pixels(region) => Some(region),
+                ManifestRegion::Tiles {
+                    col,
+                    row,
+                    cols,
+                    rows,
+                } => {
+                    let grid = grid.ok_or_else(|| {
+                        invalid_data(format!(
+                            "region {name} is given in tiles, but there is no grid"
+                        ))
+                    })?;
+                    let last_col = col.checked_add(cols.max(1) - 1);
+                    let last_row = row.checked_add(rows.max(1) - 1);
+                    let first = grid.tile(col, row);
+                    let last = last_col.zip(last_row).and_then(|(c, r)| grid.tile(c, r));
+                    first.zip(last).and_then(|(first, last)| {
+                        Some(Region {
+                            width: last.x.checked_add(last.width)? - first.x,
+                            height: last.y.checked_add(last.height)? - first.y,
+                            ..first
+                        })
+                    })
+                }
+            };
+            let region = region
+                .filter(|&region| self.contains(region))
+                .ok_or_else(|| invalid_data(format!("region {name} is outside of the image")))?;
+            regions.push((name, region));
+        }
+        self.set_grid(grid);
+        for (name, region) in regions {
+            self.insert_region(name, region);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_manifest() {
+        let mut atlas = Atlas::open("examples/heart.png", ColorMode::TrueColor, true).unwrap();
+        let manifest = r#"
+            [grid]
+            tile_width = 6
+            tile_height = 6
+            margin = 1
+            spacing = 2
+
+            [regions]
+            middle = { col = 1, row = 1 }
+            block = { col = 0, row = 0, cols = 2, rows = 2 }
+            whole = { x = 0, y = 0, width = 16, height = 16 }
+        "#;
+        atlas.load_manifest(manifest, ManifestFormat::Toml).unwrap();
+        assert_eq!(
+            atlas.grid(),
+            Some(Grid::new(6, 6).with_margin(1).with_spacing(2))
+        );
+        assert_eq!(atlas.grid_size(), (2, 2));
+        let region = |x, y, width, height| {
+            Some(Region {
+                x,
+                y,
+                width,
+                height,
+            })
+        };
+        assert_eq!(atlas.region("middle"), region(9, 9, 6, 6));
+        assert_eq!(atlas.region("block"), region(1, 1, 14, 14));
+        assert_eq!(atlas.region("whole"), region(0, 0, 16, 16));
+        assert!(atlas.named("whole", 1, 0).is_some());
+        assert!(atlas.named("missing", 1, 0).is_none());
+    }
+
+    #[test]
+    fn invalid_manifests() {
+        let mut atlas = Atlas::open("examples/heart.png", ColorMode::TrueColor, true).unwrap();
+        for (manifest, format) in [
+            (
+                r#"{ "regions": { "a": { "col": 0, "row": 0 } } }"#,
+                ManifestFormat::Json,
+            ),
+            (
+                r#"{ "regions": { "a": { "x": 0 } } }"#,
+                ManifestFormat::Json,
+            ),
+            (
+                r#"{ "regions": { "a": { "x": 8, "y": 8, "width": 9, "height": 8 } } }"#,
+                ManifestFormat::Json,
+            ),
+            (
+                r#"{ "grid": { "tile_width": 8, "tile_height": 8 },
+                     "regions": { "a": { "col": 4294967295, "row": 0, "cols": 2 } } }"#,
+                ManifestFormat::Json,
+            ),
+            (
+                r#"{ "grid": { "tile_width": 8, "tile_height": 8 },
+                     "regions": { "a": { "col": 1, "row": 1, "rows": 2 } } }"#,
+                ManifestFormat::Json,
+            ),
+            (
+                r#"{ "regions": { "a": { "x": 0, "y": 0, "width": 0, "height": 5 } } }"#,
+                ManifestFormat::Json,
+            ),
+            (
+                r#"{ "grid": { "tile_width": 0, "tile_height": 8, "spacing": 1 },
+                     "regions": { "a": { "col": 0, "row": 0 } } }"#,
+                ManifestFormat::Json,
+            ),
+            ("grid = 3", ManifestFormat::Toml),
+            ("[grid", ManifestFormat::Toml),
+        ] {
+            let error = atlas.load_manifest(manifest, format).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+        assert_eq!(atlas.grid(), None);
+    }
+
+    #[test]
+    fn open_manifest() {
+        let dir = std::env::temp_dir().join(format!("ti-manifest-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::copy("examples/heart.png", dir.join("heart.png")).unwrap();
+        fs::write(dir.join("atlas.toml"), "image = \"heart.png\"\n").unwrap();
+        let atlas = Atlas::open_manifest(dir.join("atlas.toml"), ColorMode::TrueColor, true);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(atlas.unwrap().dimensions(), (16, 16));
+    }
+}
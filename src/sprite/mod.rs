@@ -1,15 +1,28 @@
 //! Module for manipulating [`Sprite`]s, i.e. rectangular collections of [`Cell`]s with associated color information.
+mod animation;
+#[cfg(feature = "images")]
+mod aseprite;
+mod collision;
 #[cfg(feature = "images")]
 mod images;
+#[cfg(feature = "manifest")]
+mod manifest;
+mod transform;
 use std::array;
 
+pub use animation::*;
+#[cfg(feature = "images")]
+pub use aseprite::*;
+pub use collision::*;
 #[cfg(feature = "images")]
 pub use images::*;
+#[cfg(feature = "manifest")]
+pub use manifest::*;
 
 use smallvec::{smallvec, SmallVec};
 
 use crate::{
-    cell::{Cell, OffsetCell, BRAILLE_UTF8_BYTES, PIXEL_HEIGHT, PIXEL_OFFSETS, PIXEL_WIDTH},
+    cell::{Cell, BRAILLE_UTF8_BYTES, PIXEL_HEIGHT, PIXEL_OFFSETS, PIXEL_WIDTH},
     color::{Color, ColoredCell},
     units::{cell_length, from_index, index, offset_px, pos_components, px_offset},
 };
@@ -123,30 +136,24 @@ impl Sprite {
                 for y in 0..height_cells {
                     for x in 0..width_cells {
                         let i_orig = this.index(x, y, 0);
-                        let i_ul = this.index(x, y, offset);
-                        let i_ur = this.index(x + 1, y, offset);
-                        let i_dl = this.index(x, y + 1, offset);
-                        let i_dr = this.index(x + 1, y + 1, offset);
+                        let indices = [
+                            this.index(x, y, offset),
+                            this.index(x + 1, y, offset),
+                            this.index(x, y + 1, offset),
+                            this.index(x + 1, y + 1, offset),
+                        ];
                         let buf = &mut this.offsets[offset as usize];
-                        let ColoredCell { cell, color } = data[i_orig];
+                        let ColoredCell {
+                            cell,
+                            color,
+                            background,
+                        } = data[i_orig];
 
-                        match cell.with_offset(dx, dy) {
-                            OffsetCell::Aligned { cell } => {
-                                buf[i_ul].merge_cell(cell, color);
-                            }
-                            OffsetCell::Horizontal { left, right } => {
-                                buf[i_ul].merge_cell(left, color);
-                                buf[i_ur].merge_cell(right, color);
-                            }
-                            OffsetCell::Vertical { up, down } => {
-                                buf[i_ul].merge_cell(up, color);
-                                buf[i_dl].merge_cell(down, color);
-                            }
-                            OffsetCell::Corner { ul, ur, dl, dr } => {
-                                buf[i_ul].merge_cell(ul, color);
-                                buf[i_ur].merge_cell(ur, color);
-                                buf[i_dl].merge_cell(dl, color);
-                                buf[i_dr].merge_cell(dr, color);
+                        for (part, i) in cell.with_offset(dx, dy).parts().into_iter().zip(indices) {
+                            if let Some(part) = part {
+                                buf[i].merge_cell(part, color);
+                                // The background covers the whole cell, even where no pixels are set
+                                buf[i].merge_background(background);
                             }
                         }
                     }
@@ -173,7 +180,7 @@ impl Sprite {
                 for &row in s {
                     for c in row.chars() {
                         if let Some(cell) = Cell::from_braille(c) {
-                            data.push(ColoredCell { cell, color });
+                            data.push(ColoredCell::new(cell, color));
                         } else {
                             return None;
                         }
@@ -217,7 +224,36 @@ impl Sprite {
         let data: SpriteData = self.offsets[0]
             .iter()
             .copied()
-            .map(|cell| ColoredCell::new(cell.cell, f(cell)))
+            .map(|cell| ColoredCell {
+                color: f(cell),
+                ..cell
+            })
+            .collect();
+        Self::new(
+            data,
+            self.default_width(),
+            self.default_height(),
+            self.priority,
+        )
+    }
+
+    /// Creates a copy of the sprite but with all cells given the background color returned by `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::color::standard;
+    /// use ti::sprite::Sprite;
+    ///
+    /// let sprite = Sprite::from_braille_string(&["⢌"], None, 0).unwrap();
+    /// let sprite = sprite.recolor_background(|_| Some(standard::BLUE));
+    /// assert_eq!(sprite.offsets[0][0].background, Some(standard::BLUE));
+    /// ```
+    pub fn recolor_background<F: Fn(ColoredCell) -> Option<Color>>(&self, f: F) -> Self {
+        let data: SpriteData = self.offsets[0]
+            .iter()
+            .copied()
+            .map(|cell| cell.with_background(f(cell)))
             .collect();
         Self::new(
             data,
@@ -244,4 +280,111 @@ mod image_tests {
         screen.draw_sprite(&sprite, 0, 0, crate::screen::Blit::Set);
         screen.rasterize();
     }
+
+    #[test]
+    fn animation_from_gif() {
+        use image::{codecs::gif::GifEncoder, Delay, Frame, Rgba, RgbaImage};
+        use std::time::Duration;
+
+        let path = std::env::temp_dir().join(format!("ti-animation-{}.gif", std::process::id()));
+        {
+            let mut encoder = GifEncoder::new(std::fs::File::create(&path).unwrap());
+            for (x, millis) in [(0, 100), (1, 200)] {
+                let mut image = RgbaImage::new(2, 4);
+                image.put_pixel(x, 0, Rgba([255, 0, 0, 255]));
+                let delay = Delay::from_saturating_duration(Duration::from_millis(millis));
+                encoder
+                    .encode_frame(Frame::from_parts(image, 0, 0, delay))
+                    .unwrap();
+            }
+        }
+        let animation =
+            AnimatedSprite::from_animation_path(&path, ColorMode::TrueColor, 1, true, 0);
+        std::fs::remove_file(&path).unwrap();
+        let animation = animation.unwrap();
+        let frames: Vec<_> = animation
+            .frames()
+            .iter()
+            .map(|(sprite, duration)| (sprite.offsets[0][0].cell, *duration))
+            .collect();
+        assert_eq!(
+            frames,
+            [
+                (Cell::from_braille('⠁').unwrap(), Duration::from_millis(100)),
+                (Cell::from_braille('⠈').unwrap(), Duration::from_millis(200)),
+            ]
+        );
+        assert_eq!(
+            animation.frames()[0].0.offsets[0][0].color,
+            Some(Color::rgb(255, 0, 0))
+        );
+    }
+
+    #[test]
+    fn sprite_image_color_modes() {
+        let truecolor =
+            Sprite::truecolor_from_image_path("examples/heart.png", 1, true, 0).unwrap();
+        let colors: Vec<_> = truecolor.offsets[0]
+            .iter()
+            .filter_map(|c| c.color)
+            .collect();
+        assert!(!colors.is_empty());
+        assert!(colors.iter().all(|c| c.is_rgb()));
+
+        let rgb = Sprite::rgb_from_image_path("examples/heart.png", 1, true, 0).unwrap();
+        let approximated: Vec<_> = rgb.offsets[0].iter().filter_map(|c| c.color).collect();
+        assert_eq!(
+            approximated,
+            colors.iter().map(|c| c.to_ansi()).collect::<Vec<_>>()
+        );
+
+        let standard = Sprite::standard_from_image_path("examples/heart.png", 1, true, 0).unwrap();
+        assert!(standard.offsets[0]
+            .iter()
+            .filter_map(|c| c.color)
+            .all(|c| matches!(c, Color::Ansi(0..=15))));
+    }
+
+    #[test]
+    fn atlas_regions() {
+        fn shared<T: Send + Sync>(_: &T) {}
+
+        let mut atlas = Atlas::open("examples/heart.png", ColorMode::TrueColor, true).unwrap();
+        shared(&atlas);
+        let region = |x, y, width, height| Region {
+            x,
+            y,
+            width,
+            height,
+        };
+        assert!(atlas.contains(region(8, 0, 8, 16)));
+        for outside in [
+            region(9, 0, 8, 8),
+            region(0, 16, 1, 1),
+            region(1, 0, u32::MAX, 1),
+            region(0, 0, 0, 5),
+            region(0, 0, 8, 0),
+        ] {
+            assert!(!atlas.contains(outside));
+            assert!(atlas.cached_sprite(outside, 1, 0).is_none());
+        }
+        atlas.insert_region("outside", region(0, 0, 32, 32));
+        assert!(atlas.named("outside", 1, 0).is_none());
+        let duration = std::time::Duration::from_millis(100);
+        assert!(atlas.animation(8, 0, 8, 16, 1, duration, 1, 0).is_some());
+        assert!(atlas.animation(8, 0, 8, 16, 2, duration, 1, 0).is_none());
+        assert!(atlas
+            .animation(1, 0, u32::MAX / 2 + 1, 1, 3, duration, 1, 0)
+            .is_none());
+
+        let grid = Grid::new(8, 8).with_spacing(1);
+        assert_eq!(grid.tile(1, 0), Some(region(9, 0, 8, 8)));
+        assert_eq!(grid.tile(u32::MAX / 4, 0), None);
+        assert_eq!(grid.size(16, 16), (1, 1));
+        assert_eq!(grid.with_margin(3_000_000_000).size(16, 16), (0, 0));
+        assert_eq!(Grid::new(8, 8).with_spacing(u32::MAX).size(16, 16), (0, 0));
+        // Tiles of an empty grid can't be fetched
+        atlas.set_grid(Some(Grid::new(0, 8).with_spacing(1)));
+        assert!(atlas.tile(0, 0, 1, 0).is_none());
+    }
 }
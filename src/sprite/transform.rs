@@ -0,0 +1,206 @@
+//! Module for building flipped, rotated and scaled copies of sprites.
+
+use super::*;
+
+impl Sprite {
+    /// Returns whether the pixel at the given position is set, at a zero pixel offset.
+    fn pixel(&self, x: u16, y: u16) -> bool {
+        let ((x_cell, x_px), (y_cell, y_px)) = pos_components(x, y);
+        let bit = Cell::from_bit_position(x_px, y_px).unwrap();
+        self.offsets[0][index(x_cell, y_cell, self.width)].cell.bits & bit.bits != 0
+    }
+
+    /// Computes the size of the sprite in pixels, not counting unset pixels in the last cell column
+    /// and row. Those are usually padding, since sprites are made up of whole cells.
+    fn pixel_size(&self) -> (u16, u16) {
+        let mut width = self.width * PIXEL_WIDTH as u16;
+        let mut height = self.height * PIXEL_HEIGHT as u16;
+        let full_height = height;
+        for _ in 1..PIXEL_WIDTH {
+            if width == 0 || (0..full_height).any(|y| self.pixel(width - 1, y)) {
+                break;
+            }
+            width -= 1;
+        }
+        for _ in 1..PIXEL_HEIGHT {
+            if height == 0 || (0..width).any(|x| self.pixel(x, height - 1)) {
+                break;
+            }
+            height -= 1;
+        }
+        (width, height)
+    }
+
+    /// Builds a sprite of the given size in pixels, copying each of its pixels from the pixel of this
+    /// sprite at the position returned by `source`. Cells take on the colors of the cells they were
+    /// copied from.
+    fn remap<F: Fn(u16, u16) -> (u16, u16)>(&self, width: u16, height: u16, source: F) -> Self {
+        let width_cells = width.div_ceil(PIXEL_WIDTH as u16);
+        let height_cells = height.div_ceil(PIXEL_HEIGHT as u16);
+        let mut data: SpriteData =
+            smallvec![ColoredCell::default(); cell_length(width_cells, height_cells)];
+        for y in 0..height {
+            for x in 0..width {
+                let (source_x, source_y) = source(x, y);
+                let ((x_cell, x_px), (y_cell, y_px)) = pos_components(x, y);
+                let colored = &mut data[index(x_cell, y_cell, width_cells)];
+                let ((source_x_cell, _), (source_y_cell, _)) = pos_components(source_x, source_y);
+                let from = self.offsets[0][index(source_x_cell, source_y_cell, self.width)];
+                if self.pixel(source_x, source_y) {
+                    colored.merge_cell(Cell::from_bit_position(x_px, y_px).unwrap(), from.color);
+                }
+                colored.merge_background(from.background);
+            }
+        }
+        Self::new(data, width_cells, height_cells, self.priority)
+    }
+
+    /// Creates a copy of the sprite mirrored left to right.
+    ///
+    /// Unset pixels in the rightmost cell column are treated as padding, and stay on the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::sprite::Sprite;
+    ///
+    /// // Three pixels wide, with the last pixel column as padding
+    /// let sprite = Sprite::from_braille_string(&["⣿⠁"], None, 0).unwrap();
+    /// let flipped = sprite.flip_horizontal();
+    /// assert_eq!(flipped.offsets[0][0].cell.to_braille_char(), '⢹');
+    /// assert_eq!(flipped.offsets[0][1].cell.to_braille_char(), '⡇');
+    /// ```
+    pub fn flip_horizontal(&self) -> Self {
+        let (width, height) = self.pixel_size();
+        self.remap(width, height, |x, y| (width - 1 - x, y))
+    }
+
+    /// Creates a copy of the sprite mirrored top to bottom.
+    ///
+    /// Unset pixels in the bottom cell row are treated as padding, and stay at the bottom.
+    pub fn flip_vertical(&self) -> Self {
+        let (width, height) = self.pixel_size();
+        self.remap(width, height, |x, y| (x, height - 1 - y))
+    }
+
+    /// Creates a copy of the sprite rotated by 90 degrees clockwise.
+    ///
+    /// Since cells are taller than they are wide, the rotated sprite is padded with unset pixels
+    /// on the right and bottom to fill whole cells.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::sprite::Sprite;
+    ///
+    /// // A vertical line, two pixels wide and four pixels tall
+    /// let sprite = Sprite::from_braille_string(&["⣿"], None, 0).unwrap();
+    /// let rotated = sprite.rotate_90();
+    /// assert_eq!((rotated.default_width(), rotated.default_height()), (2, 1));
+    /// assert_eq!(rotated.offsets[0][0].cell.to_braille_char(), '⠛');
+    /// // Rotating back trims the padding again
+    /// let sprite = rotated.rotate_270();
+    /// assert_eq!((sprite.default_width(), sprite.default_height()), (1, 1));
+    /// ```
+    pub fn rotate_90(&self) -> Self {
+        let (width, height) = self.pixel_size();
+        self.remap(height, width, |x, y| (y, height - 1 - x))
+    }
+
+    /// Creates a copy of the sprite rotated by 180 degrees.
+    pub fn rotate_180(&self) -> Self {
+        let (width, height) = self.pixel_size();
+        self.remap(width, height, |x, y| (width - 1 - x, height - 1 - y))
+    }
+
+    /// Creates a copy of the sprite rotated by 270 degrees clockwise, i.e. 90 degrees counterclockwise.
+    ///
+    /// Like with [`Sprite::rotate_90()`], the rotated sprite is padded to fill whole cells.
+    pub fn rotate_270(&self) -> Self {
+        let (width, height) = self.pixel_size();
+        self.remap(height, width, |x, y| (width - 1 - y, x))
+    }
+
+    /// Creates a copy of the sprite scaled up by an integer factor, turning each pixel into
+    /// a `factor` by `factor` square of pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::sprite::Sprite;
+    ///
+    /// let sprite = Sprite::from_braille_string(&["⠁"], None, 0).unwrap();
+    /// let scaled = sprite.scale(2);
+    /// assert_eq!((scaled.default_width(), scaled.default_height()), (1, 1));
+    /// assert_eq!(scaled.offsets[0][0].cell.to_braille_char(), '⠛');
+    /// ```
+    pub fn scale(&self, factor: u16) -> Self {
+        let (width, height) = self.pixel_size();
+        self.remap(
+            width.saturating_mul(factor),
+            height.saturating_mul(factor),
+            |x, y| (x / factor, y / factor),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::Color;
+
+    use super::*;
+
+    /// Returns the cells of a sprite at a zero offset as braille strings.
+    fn rows(sprite: &Sprite) -> Vec<String> {
+        sprite.offsets[0]
+            .chunks(sprite.default_width().max(1) as usize)
+            .map(|row| row.iter().map(|c| c.cell.to_braille_char()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn flips() {
+        // An L shape, three pixels wide
+        let sprite = Sprite::from_braille_string(&["⡇⠀", "⠉⠁"], None, 0).unwrap();
+        assert_eq!(rows(&sprite.flip_horizontal()), ["⠀⡇", "⠉⠁"]);
+        assert_eq!(rows(&sprite.flip_vertical()), ["⡏⠁", "⠁⠀"]);
+        assert_eq!(rows(&sprite.rotate_180()), ["⠉⡇", "⠀⠁"]);
+        assert_eq!(
+            rows(&sprite.flip_horizontal().flip_horizontal()),
+            rows(&sprite)
+        );
+    }
+
+    #[test]
+    fn rotations() {
+        let sprite = Sprite::from_braille_string(&["⡏⠁"], None, 0).unwrap();
+        assert_eq!(rows(&sprite.rotate_90()), ["⠉⠹"]);
+        assert_eq!(rows(&sprite.rotate_270()), ["⠧⠤"]);
+        let mut rotated = sprite.clone();
+        for _ in 0..4 {
+            rotated = rotated.rotate_90();
+        }
+        assert_eq!(rows(&rotated), rows(&sprite));
+    }
+
+    #[test]
+    fn keeps_colors() {
+        let red = Some(Color::rgb(255, 0, 0));
+        let blue = Some(Color::rgb(0, 0, 255));
+        let mut data: SpriteData = smallvec![ColoredCell::new(Cell::full(), red); 2];
+        data[1] = ColoredCell::new(Cell::full(), blue).with_background(red);
+        let sprite = Sprite::new(data, 2, 1, 3);
+
+        let flipped = sprite.flip_horizontal();
+        assert_eq!(flipped.priority, 3);
+        assert_eq!(flipped.offsets[0][0].color, blue);
+        assert_eq!(flipped.offsets[0][0].background, red);
+        assert_eq!(flipped.offsets[0][1].color, red);
+
+        let scaled = sprite.scale(2);
+        assert_eq!(rows(&scaled), ["⣿⣿⣿⣿", "⣿⣿⣿⣿"]);
+        let colors: Vec<_> = scaled.offsets[0].iter().map(|c| c.color).collect();
+        assert_eq!(colors, [red, red, blue, blue, red, red, blue, blue]);
+        assert_eq!(sprite.scale(0).offsets[0].len(), 0);
+    }
+}
@@ -0,0 +1,449 @@
+//! Module for drawing grids of tiles, e.g. the levels of a game.
+//!
+//! A [`Tilemap`] is made up of [`TileLayer`]s, which hold indices into a [`Tileset`] of sprites.
+//! With the `tiled` feature, maps can be loaded from files made with the [Tiled](https://www.mapeditor.org) editor.
+#[cfg(feature = "tiled")]
+mod tiled;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[cfg(feature = "images")]
+use crate::sprite::Atlas;
+use crate::{
+    cell::{PIXEL_HEIGHT, PIXEL_WIDTH},
+    color::Color,
+    screen::{Blit, Screen},
+    sprite::Sprite,
+};
+
+/// A value attached to a tile, layer or map, e.g. whether a tile is solid.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Property {
+    /// A boolean value.
+    Bool(bool),
+    /// An integer value.
+    Int(i64),
+    /// A floating point value.
+    Float(f64),
+    /// A string value.
+    String(String),
+    /// A color value.
+    Color(Color),
+}
+
+/// Named [`Property`] values.
+pub type Properties = HashMap<String, Property>;
+
+/// A list of tile sprites, and the properties of each tile.
+///
+/// Tiles are referred to by their index in the list. Sprites are reference counted, so that
+/// they can be shared with the atlas they were read from.
+#[derive(Debug, Clone, Default)]
+pub struct Tileset {
+    tiles: Vec<Arc<Sprite>>,
+    properties: HashMap<u32, Properties>,
+}
+
+impl Tileset {
+    /// Creates an empty tileset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a tileset from all tiles of the grid of an atlas. See [`Tileset::push_atlas()`].
+    #[cfg(feature = "images")]
+    pub fn from_atlas(atlas: &Atlas, scale: u16, priority: u16) -> Self {
+        let mut tileset = Self::new();
+        tileset.push_atlas(atlas, scale, priority);
+        tileset
+    }
+
+    /// Adds a tile to the end of the tileset. Returns the index of the new tile.
+    pub fn push(&mut self, sprite: Arc<Sprite>) -> u32 {
+        self.tiles.push(sprite);
+        self.tiles.len() as u32 - 1
+    }
+
+    /// Adds all tiles of the grid of an atlas to the end of the tileset, counting left to right and
+    /// then top to bottom. Returns the index of the first new tile.
+    ///
+    /// No tiles are added if the atlas has no grid.
+    #[cfg(feature = "images")]
+    pub fn push_atlas(&mut self, atlas: &Atlas, scale: u16, priority: u16) -> u32 {
+        let first = self.tiles.len() as u32;
+        let (cols, rows) = atlas.grid_size();
+        for i in 0..cols * rows {
+            if let Some(sprite) = atlas.tile_index(i, scale, priority) {
+                self.tiles.push(sprite);
+            }
+        }
+        first
+    }
+
+    /// Returns the sprite of the tile with the given index.
+    pub fn get(&self, tile: u32) -> Option<&Arc<Sprite>> {
+        self.tiles.get(tile as usize)
+    }
+
+    /// Returns the number of tiles in the tileset.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Returns `true` if the tileset has no tiles.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Returns the properties of the tile with the given index, if it has any.
+    pub fn properties(&self, tile: u32) -> Option<&Properties> {
+        self.properties.get(&tile)
+    }
+
+    /// Returns the property of the tile with the given index and name.
+    pub fn property(&self, tile: u32, name: &str) -> Option<&Property> {
+        self.properties(tile)?.get(name)
+    }
+
+    /// Sets the properties of the tile with the given index, replacing any previous properties.
+    pub fn set_properties(&mut self, tile: u32, properties: Properties) {
+        self.properties.insert(tile, properties);
+    }
+}
+
+/// A grid of tiles. Each position holds the index of a tile in a [`Tileset`], or `None` if it is empty.
+#[derive(Debug, Clone)]
+pub struct TileLayer {
+    /// The name of the layer
+    pub name: String,
+    /// Whether the layer is drawn by [`Tilemap::draw()`]
+    pub visible: bool,
+    /// The draw priority of the tiles in the layer. Tiles of higher priority layers will be drawn
+    /// on top of lower priority ones.
+    pub priority: u16,
+    /// The position of the layer relative to the map, in pixels
+    pub offset: (i32, i32),
+    /// The properties of the layer
+    pub properties: Properties,
+    width: u16,
+    height: u16,
+    tiles: Vec<Option<u32>>,
+}
+
+impl TileLayer {
+    /// Creates an empty, visible layer with the given dimensions in tiles.
+    pub fn new<S: Into<String>>(name: S, width: u16, height: u16) -> Self {
+        Self {
+            name: name.into(),
+            visible: true,
+            priority: 0,
+            offset: (0, 0),
+            properties: Properties::new(),
+            width,
+            height,
+            tiles: vec![None; width as usize * height as usize],
+        }
+    }
+
+    /// Creates a visible layer from rows of tiles, given from left to right and top to bottom.
+    ///
+    /// Returns `None` if the number of tiles isn't a multiple of the width, or the layer would be
+    /// too tall.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ti::tilemap::TileLayer;
+    ///
+    /// let layer = TileLayer::from_tiles("ground", 2, vec![Some(0), None, None, Some(1)]).unwrap();
+    /// assert_eq!(layer.height(), 2);
+    /// assert_eq!(layer.get(1, 1), Some(1));
+    /// assert!(TileLayer::from_tiles("ground", 2, vec![None; 3]).is_none());
+    /// ```
+    pub fn from_tiles<S: Into<String>>(
+        name: S,
+        width: u16,
+        tiles: Vec<Option<u32>>,
+    ) -> Option<Self> {
+        let height = match tiles.len().checked_div(width as usize) {
+            Some(height) if height * width as usize == tiles.len() => height,
+            Some(_) => return None,
+            None if tiles.is_empty() => 0,
+            None => return None,
+        };
+        let height = u16::try_from(height).ok()?;
+        Some(Self {
+            tiles,
+            ..Self::new(name, width, height)
+        })
+    }
+
+    /// Sets the draw priority of the layer.
+    pub fn with_priority(mut self, priority: u16) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Returns the width of the layer in tiles.
+    pub const fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Returns the height of the layer in tiles.
+    pub const fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the tile at the given position, or `None` if the position is empty or outside of the layer.
+    pub fn get(&self, x: u16, y: u16) -> Option<u32> {
+        if x < self.width && y < self.height {
+            self.tiles[y as usize * self.width as usize + x as usize]
+        } else {
+            None
+        }
+    }
+
+    /// Sets the tile at the given position.
+    ///
+    /// Returns `false` if the position is outside of the layer.
+    pub fn set(&mut self, x: u16, y: u16, tile: Option<u32>) -> bool {
+        if x < self.width && y < self.height {
+            self.tiles[y as usize * self.width as usize + x as usize] = tile;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Sets every position of the layer to the given tile.
+    pub fn fill(&mut self, tile: Option<u32>) {
+        self.tiles.fill(tile);
+    }
+}
+
+/// Layers of tiles drawn from a [`Tileset`], on a grid with a fixed tile size.
+///
+/// Tiles are drawn with the top left corner of their sprite at the top left corner of their
+/// grid square. Only the tiles within the bounds of the screen are drawn, assuming each tile
+/// fits within its grid square.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use ti::screen::{Blit, Screen};
+/// use ti::sprite::Sprite;
+/// use ti::tilemap::{TileLayer, Tilemap, Tileset};
+///
+/// let mut tileset = Tileset::new();
+/// let wall = tileset.push(Arc::new(Sprite::rectangle(2, 4, None, 0)));
+/// let mut layer = TileLayer::new("walls", 8, 1);
+/// layer.fill(Some(wall));
+///
+/// let mut map = Tilemap::new(2, 4, tileset);
+/// map.push_layer(layer);
+///
+/// let mut screen = Screen::new_cells(3, 1);
+/// map.draw(&mut screen, 1, 0, Blit::Add);
+/// assert_eq!(screen.rasterize(), "⣿⣿⣿\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tilemap {
+    tile_width: u16,
+    tile_height: u16,
+    tileset: Tileset,
+    layers: Vec<TileLayer>,
+    /// The properties of the map
+    pub properties: Properties,
+}
+
+impl Tilemap {
+    /// Creates a map without layers, with the given tile size in pixels.
+    pub fn new(tile_width: u16, tile_height: u16, tileset: Tileset) -> Self {
+        Self {
+            tile_width,
+            tile_height,
+            tileset,
+            layers: vec![],
+            properties: Properties::new(),
+        }
+    }
+
+    /// Returns the size of the tiles of the map, in pixels.
+    pub const fn tile_size(&self) -> (u16, u16) {
+        (self.tile_width, self.tile_height)
+    }
+
+    /// Returns the size of the map in pixels, i.e. the size of its largest layer.
+    pub fn pixel_size(&self) -> (u32, u32) {
+        self.layers.iter().fold((0, 0), |(width, height), layer| {
+            (
+                width.max(layer.width as u32 * self.tile_width as u32),
+                height.max(layer.height as u32 * self.tile_height as u32),
+            )
+        })
+    }
+
+    /// Returns the tileset of the map.
+    pub const fn tileset(&self) -> &Tileset {
+        &self.tileset
+    }
+
+    /// Returns the tileset of the map, mutably.
+    pub fn tileset_mut(&mut self) -> &mut Tileset {
+        &mut self.tileset
+    }
+
+    /// Adds a layer on top of the existing layers.
+    pub fn push_layer(&mut self, layer: TileLayer) {
+        self.layers.push(layer);
+    }
+
+    /// Returns the layers of the map, from the bottom up.
+    pub fn layers(&self) -> &[TileLayer] {
+        &self.layers
+    }
+
+    /// Returns the layers of the map mutably, from the bottom up.
+    pub fn layers_mut(&mut self) -> &mut [TileLayer] {
+        &mut self.layers
+    }
+
+    /// Returns the first layer with the given name.
+    pub fn layer(&self, name: &str) -> Option<&TileLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    /// Returns the first layer with the given name, mutably.
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut TileLayer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    /// Returns the tile of a layer at the given pixel position of the map, taking the layer's
+    /// offset into account.
+    pub fn tile_at(&self, layer: &TileLayer, x_pixel: i32, y_pixel: i32) -> Option<u32> {
+        if self.tile_width == 0 || self.tile_height == 0 {
+            return None;
+        }
+        let x = (x_pixel - layer.offset.0).div_euclid(self.tile_width as i32);
+        let y = (y_pixel - layer.offset.1).div_euclid(self.tile_height as i32);
+        layer.get(u16::try_from(x).ok()?, u16::try_from(y).ok()?)
+    }
+
+    /// Draws the visible layers of the map to a screen, from the bottom up. The scroll offset is the
+    /// pixel position of the map that is drawn at the top left corner of the screen, and may be negative.
+    ///
+    /// Tiles that don't line up with the cells of the screen share cells with their neighbours.
+    /// Use [`Blit::Add`] rather than [`Blit::Set`] to keep the pixels of both tiles in those cells.
+    pub fn draw(&self, screen: &mut Screen, scroll_x: i32, scroll_y: i32, blit: Blit) {
+        for layer in self.layers.iter().filter(|layer| layer.visible) {
+            self.draw_layer(layer, screen, scroll_x, scroll_y, blit);
+        }
+    }
+
+    /// Draws a single layer to a screen, whether it is visible or not. See [`Tilemap::draw()`].
+    pub fn draw_layer(
+        &self,
+        layer: &TileLayer,
+        screen: &mut Screen,
+        scroll_x: i32,
+        scroll_y: i32,
+        blit: Blit,
+    ) {
+        let (tile_width, tile_height) = (self.tile_width as i32, self.tile_height as i32);
+        if tile_width == 0 || tile_height == 0 {
+            return;
+        }
+        let x_origin = layer.offset.0 - scroll_x;
+        let y_origin = layer.offset.1 - scroll_y;
+        let screen_width = screen.width() as i32 * PIXEL_WIDTH as i32;
+        let screen_height = screen.height() as i32 * PIXEL_HEIGHT as i32;
+        let visible = |origin: i32, tile_size: i32, screen_size: i32, tiles: u16| {
+            let first = (-origin).div_euclid(tile_size).clamp(0, tiles as i32);
+            let last = (screen_size - origin + tile_size - 1)
+                .div_euclid(tile_size)
+                .clamp(0, tiles as i32);
+            first as u16..last as u16
+        };
+        for y in visible(y_origin, tile_height, screen_height, layer.height) {
+            for x in visible(x_origin, tile_width, screen_width, layer.width) {
+                let Some(sprite) = layer.get(x, y).and_then(|tile| self.tileset.get(tile)) else {
+                    continue;
+                };
+                let x_pixel = x_origin + x as i32 * tile_width;
+                let y_pixel = y_origin + y as i32 * tile_height;
+                screen.draw_sprite_with_priority(sprite, x_pixel, y_pixel, blit, layer.priority);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::standard;
+
+    use super::*;
+
+    fn map() -> Tilemap {
+        let mut tileset = Tileset::new();
+        tileset.push(Arc::new(Sprite::rectangle(2, 4, None, 0)));
+        tileset.push(Arc::new(
+            Sprite::from_braille_string(&["⠁"], Some(standard::RED), 0).unwrap(),
+        ));
+        let mut map = Tilemap::new(2, 4, tileset);
+        map.push_layer(TileLayer::from_tiles("ground", 3, vec![Some(0), None, Some(0)]).unwrap());
+        map.push_layer(
+            TileLayer::from_tiles("items", 3, vec![None, Some(1), Some(1)])
+                .unwrap()
+                .with_priority(1),
+        );
+        map
+    }
+
+    #[test]
+    fn draw_aligned() {
+        let map = map();
+        let mut screen = Screen::new_cells(3, 2);
+        map.draw(&mut screen, 0, -4, Blit::Set);
+        assert_eq!(screen.rasterize(), "⠀⠀⠀\n⣿⠁⠁\n");
+        assert_eq!(screen.get_color(1, 1), Some(standard::RED));
+
+        let mut screen = Screen::new_cells(3, 1);
+        map.draw(&mut screen, 2, 0, Blit::Set);
+        assert_eq!(screen.rasterize(), "⠁⠁⠀\n");
+    }
+
+    #[test]
+    fn draw_unaligned() {
+        let mut map = map();
+        map.layers_mut()[1].visible = false;
+        let mut screen = Screen::new_cells(3, 1);
+        map.draw(&mut screen, -1, 0, Blit::Set);
+        assert_eq!(screen.rasterize(), "⢸⡇⢸\n");
+
+        let items = map.layer("items").unwrap();
+        let mut screen = Screen::new_cells(3, 1);
+        map.draw_layer(items, &mut screen, 1, 0, Blit::Set);
+        assert_eq!(screen.rasterize(), "⠈⠈⠀\n");
+    }
+
+    #[test]
+    fn tile_lookup() {
+        let mut map = map();
+        map.tileset_mut().set_properties(
+            0,
+            Properties::from([("solid".to_string(), Property::Bool(true))]),
+        );
+        let ground = map.layer("ground").unwrap();
+        assert_eq!(map.tile_at(ground, 5, 3), Some(0));
+        assert_eq!(map.tile_at(ground, 3, 0), None);
+        assert_eq!(map.tile_at(ground, -1, 0), None);
+        assert_eq!(
+            map.tileset().property(0, "solid"),
+            Some(&Property::Bool(true))
+        );
+        assert_eq!(map.pixel_size(), (6, 4));
+    }
+}
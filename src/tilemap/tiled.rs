@@ -0,0 +1,861 @@
+//! Module for loading maps made with the [Tiled](https://www.mapeditor.org) editor.
+
+use super::*;
+
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::{fs, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use image::{ImageError, ImageResult};
+use roxmltree::{Document, Node};
+use serde::Deserialize;
+
+use crate::sprite::{ColorMode, Grid};
+
+/// Bits of a global tile id that flip or rotate the tile.
+const FLIP_FLAGS: u32 = 0xf000_0000;
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// A tileset of a map, with its tiles numbered from `first_gid`.
+struct TiledTileset {
+    first_gid: u32,
+    image: PathBuf,
+    grid: Grid,
+    /// The number of columns of tiles, as counted by Tiled. This may differ from the size of the
+    /// grid, since Tiled only leaves room for the margin on one side.
+    columns: u32,
+    tile_count: u32,
+    tiles: Vec<(u32, Properties)>,
+}
+
+/// A tile layer of a map, with its tiles given by global tile ids.
+struct TiledLayer {
+    layer: TileLayer,
+    gids: Vec<u32>,
+}
+
+/// The contents of a map file, before its tileset images are loaded.
+#[derive(Default)]
+struct TiledMap {
+    tile_width: u16,
+    tile_height: u16,
+    properties: Properties,
+    tilesets: Vec<TiledTileset>,
+    layers: Vec<TiledLayer>,
+}
+
+/// Parses a property value of the given Tiled type. Returns `None` for types that have no
+/// equivalent [`Property`].
+fn parse_property(kind: &str, value: &str) -> io::Result<Option<Property>> {
+    fn parse<T: FromStr>(kind: &str, value: &str) -> io::Result<T> {
+        value
+            .parse()
+            .map_err(|_| invalid_data(format!("invalid {kind} property {value:?}")))
+    }
+    Ok(Some(match kind {
+        "bool" => Property::Bool(parse(kind, value)?),
+        "int" | "object" => Property::Int(parse(kind, value)?),
+        "float" => Property::Float(parse(kind, value)?),
+        "color" => match parse_color(value) {
+            Some(color) => Property::Color(color),
+            None => return Ok(None),
+        },
+        "class" => return Ok(None),
+        _ => Property::String(value.to_string()),
+    }))
+}
+
+/// Parses a color written as `#AARRGGBB` or `#RRGGBB`, ignoring the alpha channel.
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    let hex = match hex.len() {
+        8 => &hex[2..],
+        6 => hex,
+        _ => return None,
+    };
+    let rgb = u32::from_str_radix(hex, 16).ok()?;
+    Some(Color::rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+}
+
+/// Decodes the tile data of a layer, given as CSV or base64.
+fn decode_gids(data: &str, encoding: &str, compression: Option<&str>) -> io::Result<Vec<u32>> {
+    match encoding {
+        "csv" => data
+            .split(',')
+            .map(|gid| gid.trim().parse().map_err(invalid_data))
+            .collect(),
+        "base64" => {
+            let bytes = STANDARD.decode(data.trim()).map_err(invalid_data)?;
+            let bytes = match compression.unwrap_or("") {
+                "" => bytes,
+                "zlib" => read_all(ZlibDecoder::new(&bytes[..]))?,
+                "gzip" => read_all(GzDecoder::new(&bytes[..]))?,
+                compression => {
+                    return Err(invalid_data(format!(
+                        "unsupported compression {compression}"
+                    )))
+                }
+            };
+            Ok(bytes
+                .chunks_exact(4)
+                .map(|gid| u32::from_le_bytes(gid.try_into().unwrap()))
+                .collect())
+        }
+        encoding => Err(invalid_data(format!("unsupported encoding {encoding}"))),
+    }
+}
+
+fn read_all<R: Read>(mut reader: R) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Returns the directory containing a file, for resolving the paths it refers to.
+fn directory(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new(""))
+}
+
+fn attribute<T: FromStr>(node: Node, name: &str) -> io::Result<Option<T>> {
+    node.attribute(name)
+        .map(|value| {
+            value.parse().map_err(|_| {
+                invalid_data(format!(
+                    "invalid {name} attribute of <{}>",
+                    node.tag_name().name()
+                ))
+            })
+        })
+        .transpose()
+}
+
+fn required<T: FromStr>(node: Node, name: &str) -> io::Result<T> {
+    attribute(node, name)?.ok_or_else(|| {
+        invalid_data(format!(
+            "missing {name} attribute of <{}>",
+            node.tag_name().name()
+        ))
+    })
+}
+
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |child| child.has_tag_name(name))
+}
+
+/// Parses the `<properties>` of an element.
+fn xml_properties(node: Node) -> io::Result<Properties> {
+    let mut properties = Properties::new();
+    for property in children(node, "properties").flat_map(|node| children(node, "property")) {
+        let name: String = required(property, "name")?;
+        let kind = property.attribute("type").unwrap_or("string");
+        // Multi-line strings are stored as text rather than in an attribute
+        let value = property
+            .attribute("value")
+            .or(property.text())
+            .unwrap_or("");
+        if let Some(value) = parse_property(kind, value)? {
+            properties.insert(name, value);
+        }
+    }
+    Ok(properties)
+}
+
+/// Parses a `<tileset>` element, from a map or a `.tsx` file in the given directory.
+fn xml_tileset(node: Node, first_gid: u32, directory: &Path) -> io::Result<TiledTileset> {
+    let image = children(node, "image")
+        .next()
+        .ok_or_else(|| invalid_data("tilesets without a single image are not supported"))?;
+    let grid = Grid::new(required(node, "tilewidth")?, required(node, "tileheight")?)
+        .with_margin(attribute(node, "margin")?.unwrap_or(0))
+        .with_spacing(attribute(node, "spacing")?.unwrap_or(0));
+    let mut tiles = vec![];
+    for tile in children(node, "tile") {
+        tiles.push((required(tile, "id")?, xml_properties(tile)?));
+    }
+    Ok(TiledTileset {
+        first_gid,
+        image: directory.join(required::<String>(image, "source")?),
+        grid,
+        columns: required(node, "columns")?,
+        tile_count: required(node, "tilecount")?,
+        tiles,
+    })
+}
+
+/// Parses the layers among the children of a `<map>` or `<group>` element, flattening groups.
+fn xml_layers(node: Node, offset: (i32, i32), visible: bool, map: &mut TiledMap) -> io::Result<()> {
+    for child in node.children() {
+        let name = child.tag_name().name();
+        if name != "layer" && name != "group" {
+            continue;
+        }
+        let offset = (
+            offset.0 + attribute::<f64>(child, "offsetx")?.unwrap_or(0.0) as i32,
+            offset.1 + attribute::<f64>(child, "offsety")?.unwrap_or(0.0) as i32,
+        );
+        let visible = visible && attribute::<u8>(child, "visible")?.unwrap_or(1) != 0;
+        if name == "group" {
+            xml_layers(child, offset, visible, map)?;
+            continue;
+        }
+        let width = required(child, "width")?;
+        let data = children(child, "data")
+            .next()
+            .ok_or_else(|| invalid_data("missing layer data"))?;
+        if children(data, "chunk").next().is_some() {
+            return Err(invalid_data("infinite maps are not supported"));
+        }
+        let gids = match data.attribute("encoding") {
+            Some(encoding) => decode_gids(
+                data.text().unwrap_or(""),
+                encoding,
+                data.attribute("compression"),
+            )?,
+            None => children(data, "tile")
+                .map(|tile| Ok(attribute(tile, "gid")?.unwrap_or(0)))
+                .collect::<io::Result<_>>()?,
+        };
+        let mut layer = TileLayer::new(child.attribute("name").unwrap_or(""), width, 0);
+        layer.visible = visible;
+        layer.offset = offset;
+        layer.properties = xml_properties(child)?;
+        map.layers.push(TiledLayer { layer, gids });
+    }
+    Ok(())
+}
+
+impl TiledMap {
+    /// Parses a map in the XML format of Tiled (`.tmx`). External tilesets are read relative to
+    /// the given directory.
+    fn from_tmx(text: &str, directory: &Path) -> io::Result<Self> {
+        let document = Document::parse(text).map_err(invalid_data)?;
+        let root = document.root_element();
+        if !root.has_tag_name("map") {
+            return Err(invalid_data("missing <map> element"));
+        }
+        check_map(
+            root.attribute("orientation"),
+            attribute::<u8>(root, "infinite")?.unwrap_or(0) != 0,
+        )?;
+        let mut map = Self {
+            tile_width: required(root, "tilewidth")?,
+            tile_height: required(root, "tileheight")?,
+            properties: xml_properties(root)?,
+            ..Self::default()
+        };
+        for node in children(root, "tileset") {
+            let first_gid = required(node, "firstgid")?;
+            let tileset = match node.attribute("source") {
+                Some(source) => load_tileset(&directory.join(source), first_gid)?,
+                None => xml_tileset(node, first_gid, directory)?,
+            };
+            map.tilesets.push(tileset);
+        }
+        map.check_tilesets()?;
+        xml_layers(root, (0, 0), true, &mut map)?;
+        Ok(map)
+    }
+
+    /// Parses a map in the JSON format of Tiled (`.tmj` or `.json`). External tilesets are read
+    /// relative to the given directory.
+    fn from_tmj(text: &str, directory: &Path) -> io::Result<Self> {
+        let json: JsonMap = serde_json::from_str(text).map_err(invalid_data)?;
+        check_map(json.orientation.as_deref(), json.infinite)?;
+        let mut map = Self {
+            tile_width: json.tilewidth,
+            tile_height: json.tileheight,
+            properties: json_properties(json.properties)?,
+            ..Self::default()
+        };
+        for tileset in json.tilesets {
+            let first_gid = tileset
+                .firstgid
+                .ok_or_else(|| invalid_data("missing firstgid of tileset"))?;
+            let tileset = match &tileset.source {
+                Some(source) => load_tileset(&directory.join(source), first_gid)?,
+                None => tileset.into_tileset(first_gid, directory)?,
+            };
+            map.tilesets.push(tileset);
+        }
+        map.check_tilesets()?;
+        json_layers(json.layers, (0, 0), true, &mut map)?;
+        Ok(map)
+    }
+
+    /// Checks that the tiles of every tileset fill the squares of the map. Tiled draws larger
+    /// tiles anchored at the bottom left of their square, which tile layers can't represent.
+    fn check_tilesets(&self) -> io::Result<()> {
+        let tile_size = (self.tile_width as u32, self.tile_height as u32);
+        if self
+            .tilesets
+            .iter()
+            .any(|tileset| (tileset.grid.tile_width, tileset.grid.tile_height) != tile_size)
+        {
+            return Err(invalid_data(
+                "tilesets with a different tile size than the map are not supported",
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn check_map(orientation: Option<&str>, infinite: bool) -> io::Result<()> {
+    if orientation.is_some_and(|orientation| orientation != "orthogonal") {
+        return Err(invalid_data("only orthogonal maps are supported"));
+    }
+    if infinite {
+        return Err(invalid_data("infinite maps are not supported"));
+    }
+    Ok(())
+}
+
+/// Loads an external tileset, in XML (`.tsx`) or JSON (`.tsj` or `.json`) depending on its extension.
+fn load_tileset(path: &Path, first_gid: u32) -> io::Result<TiledTileset> {
+    let text = fs::read_to_string(path)?;
+    if is_json(path) {
+        serde_json::from_str::<JsonTileset>(&text)
+            .map_err(invalid_data)?
+            .into_tileset(first_gid, directory(path))
+    } else {
+        let document = Document::parse(&text).map_err(invalid_data)?;
+        xml_tileset(document.root_element(), first_gid, directory(path))
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            ["tmj", "tsj", "json"]
+                .iter()
+                .any(|json| extension.eq_ignore_ascii_case(json))
+        })
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonProperty {
+    name: String,
+    #[serde(rename = "type", default)]
+    kind: Option<String>,
+    value: serde_json::Value,
+}
+
+fn json_properties(properties: Vec<JsonProperty>) -> io::Result<Properties> {
+    let mut parsed = Properties::new();
+    for property in properties {
+        let value = match property.value {
+            serde_json::Value::String(value) => value,
+            value => value.to_string(),
+        };
+        let kind = property.kind.as_deref().unwrap_or("string");
+        if let Some(value) = parse_property(kind, &value)? {
+            parsed.insert(property.name, value);
+        }
+    }
+    Ok(parsed)
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonMap {
+    orientation: Option<String>,
+    #[serde(default)]
+    infinite: bool,
+    tilewidth: u16,
+    tileheight: u16,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+    #[serde(default)]
+    tilesets: Vec<JsonTileset>,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonTileset {
+    firstgid: Option<u32>,
+    source: Option<String>,
+    image: Option<String>,
+    tilewidth: Option<u32>,
+    tileheight: Option<u32>,
+    #[serde(default)]
+    margin: u32,
+    #[serde(default)]
+    spacing: u32,
+    columns: Option<u32>,
+    tilecount: Option<u32>,
+    #[serde(default)]
+    tiles: Vec<JsonTile>,
+}
+
+impl JsonTileset {
+    fn into_tileset(self, first_gid: u32, directory: &Path) -> io::Result<TiledTileset> {
+        let image = self
+            .image
+            .ok_or_else(|| invalid_data("tilesets without a single image are not supported"))?;
+        let (Some(tile_width), Some(tile_height)) = (self.tilewidth, self.tileheight) else {
+            return Err(invalid_data("missing tile size of tileset"));
+        };
+        let (Some(columns), Some(tile_count)) = (self.columns, self.tilecount) else {
+            return Err(invalid_data("missing tile count of tileset"));
+        };
+        let mut tiles = vec![];
+        for tile in self.tiles {
+            tiles.push((tile.id, json_properties(tile.properties)?));
+        }
+        Ok(TiledTileset {
+            first_gid,
+            image: directory.join(image),
+            grid: Grid::new(tile_width, tile_height)
+                .with_margin(self.margin)
+                .with_spacing(self.spacing),
+            columns,
+            tile_count,
+            tiles,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonTile {
+    id: u32,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+/// The tile data of a layer, either as an array of global tile ids or encoded as a string.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonData {
+    Gids(Vec<u32>),
+    Encoded(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonLayer {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    width: u16,
+    data: Option<JsonData>,
+    encoding: Option<String>,
+    compression: Option<String>,
+    #[serde(default = "visible")]
+    visible: bool,
+    #[serde(default)]
+    offsetx: f64,
+    #[serde(default)]
+    offsety: f64,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+}
+
+const fn visible() -> bool {
+    true
+}
+
+/// Parses a list of JSON layers, flattening groups.
+fn json_layers(
+    layers: Vec<JsonLayer>,
+    offset: (i32, i32),
+    visible: bool,
+    map: &mut TiledMap,
+) -> io::Result<()> {
+    for json in layers {
+        let offset = (
+            offset.0 + json.offsetx as i32,
+            offset.1 + json.offsety as i32,
+        );
+        let visible = visible && json.visible;
+        match json.kind.as_str() {
+            "group" => json_layers(json.layers, offset, visible, map)?,
+            "tilelayer" => {
+                let gids = match json.data {
+                    Some(JsonData::Gids(gids)) => gids,
+                    Some(JsonData::Encoded(data)) => decode_gids(
+                        &data,
+                        json.encoding.as_deref().unwrap_or("base64"),
+                        json.compression.as_deref(),
+                    )?,
+                    None => return Err(invalid_data("infinite maps are not supported")),
+                };
+                let mut layer = TileLayer::new(json.name, json.width, 0);
+                layer.visible = visible;
+                layer.offset = offset;
+                layer.properties = json_properties(json.properties)?;
+                map.layers.push(TiledLayer { layer, gids });
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+impl Tilemap {
+    /// Opens a map made with the [Tiled](https://www.mapeditor.org) editor, in its XML format (`.tmx`)
+    /// or its JSON format (`.tmj` or `.json`) depending on the extension.
+    ///
+    /// Every tile layer of the map becomes a [`TileLayer`], including the layers inside groups.
+    /// Layers are given increasing priorities from the bottom up, so that higher layers are drawn on top.
+    /// The properties of the map, its layers and its tiles are kept, except for class properties.
+    ///
+    /// Tilesets can be embedded in the map or stored in separate `.tsx`, `.tsj` or `.json` files.
+    /// Each tileset must be made from a single image, which is opened as an [`Atlas`] with the given
+    /// `color_mode` and `use_alpha_channel` settings. All tilesets are merged into the map's [`Tileset`].
+    ///
+    /// Only orthogonal maps with a fixed size are supported, and the tiles of every tileset must have
+    /// the tile size of the map. Flipped and rotated tiles are drawn unflipped, and object layers
+    /// and image layers are skipped. Tiles that aren't in any tileset are left empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ImageError`] if a tileset image can't be loaded, or an [`ImageError::IoError`] of kind
+    /// [`io::ErrorKind::InvalidData`] if the map or its tilesets are invalid or unsupported.
+    pub fn open_tiled<P: AsRef<Path>>(
+        path: P,
+        color_mode: ColorMode,
+        use_alpha_channel: bool,
+    ) -> ImageResult<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let map = if is_json(path) {
+            TiledMap::from_tmj(&text, directory(path))?
+        } else {
+            TiledMap::from_tmx(&text, directory(path))?
+        };
+
+        let mut tileset = Tileset::new();
+        // The first global tile id of each tileset, the index of its first tile in the merged tileset
+        // and its number of tiles
+        let mut first_tiles = vec![];
+        for tiled in map.tilesets {
+            let atlas = Atlas::open(&tiled.image, color_mode, use_alpha_channel)?;
+            let first = tileset.len() as u32;
+            let count = tiled.tile_count;
+            // Tiles are numbered by Tiled's count of columns, rather than the size of the grid
+            let region = |id: u32| {
+                let col = id.checked_rem(tiled.columns)?;
+                tiled.grid.tile(col, id / tiled.columns)
+            };
+            for id in 0..count {
+                let sprite = region(id)
+                    .and_then(|region| atlas.cached_sprite(region, 1, 0))
+                    .ok_or_else(|| {
+                        ImageError::IoError(invalid_data(format!(
+                            "tile {id} is outside of the image {}",
+                            tiled.image.display()
+                        )))
+                    })?;
+                tileset.push(sprite);
+            }
+            for (id, properties) in tiled.tiles {
+                // Tiles outside of the image have no sprite to attach the properties to
+                if id < count && !properties.is_empty() {
+                    tileset.set_properties(first + id, properties);
+                }
+            }
+            first_tiles.push((tiled.first_gid, first, count));
+        }
+        first_tiles.sort_unstable();
+
+        let mut tilemap = Self::new(map.tile_width, map.tile_height, tileset);
+        tilemap.properties = map.properties;
+        for (priority, TiledLayer { layer, gids }) in map.layers.into_iter().enumerate() {
+            let tiles = gids
+                .into_iter()
+                .map(|gid| {
+                    let gid = gid & !FLIP_FLAGS;
+                    let i = first_tiles.partition_point(|&(first_gid, ..)| first_gid <= gid);
+                    match i.checked_sub(1).map(|i| first_tiles[i]) {
+                        Some((first_gid, first, count)) if gid != 0 => {
+                            let id = gid - first_gid;
+                            (id < count).then_some(first + id)
+                        }
+                        _ => None,
+                    }
+                })
+                .collect();
+            let layer = TileLayer::from_tiles("", layer.width, tiles)
+                .map(|tiles| TileLayer {
+                    priority: priority as u16,
+                    height: tiles.height,
+                    tiles: tiles.tiles,
+                    ..layer
+                })
+                .ok_or_else(|| {
+                    ImageError::IoError(invalid_data("layer data doesn't match its width"))
+                })?;
+            tilemap.push_layer(layer);
+        }
+        Ok(tilemap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{
+        write::{GzEncoder, ZlibEncoder},
+        Compression,
+    };
+    use image::{Rgba, RgbaImage};
+
+    use super::*;
+
+    fn encode(gids: &[u32], gzip: bool) -> String {
+        let bytes: Vec<u8> = gids.iter().flat_map(|gid| gid.to_le_bytes()).collect();
+        let compressed = if gzip {
+            let mut encoder = GzEncoder::new(vec![], Compression::default());
+            encoder.write_all(&bytes).unwrap();
+            encoder.finish().unwrap()
+        } else {
+            let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+            encoder.write_all(&bytes).unwrap();
+            encoder.finish().unwrap()
+        };
+        STANDARD.encode(compressed)
+    }
+
+    const TMX: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" width="3" height="1" tilewidth="2" tileheight="4" infinite="0">
+ <properties>
+  <property name="sky" type="color" value="#ff00ff00"/>
+  <property name="level" type="int" value="3"/>
+ </properties>
+ <tileset firstgid="1" name="inline" tilewidth="2" tileheight="4" tilecount="2" columns="2">
+  <image source="tiles.png" width="4" height="4"/>
+  <tile id="1">
+   <properties>
+    <property name="solid" type="bool" value="true"/>
+   </properties>
+  </tile>
+ </tileset>
+ <tileset firstgid="3" source="extra.tsj"/>
+ <layer id="1" name="ground" width="3" height="1">
+  <data encoding="csv">
+1,0,3
+</data>
+ </layer>
+ <group id="2" name="top" offsetx="2">
+  <layer id="3" name="items" width="3" height="1" offsetx="-2">
+   <properties>
+    <property name="note">multiple
+lines</property>
+   </properties>
+   <data encoding="base64" compression="zlib">ITEMS</data>
+  </layer>
+  <group id="4" name="off" visible="0">
+   <layer id="5" name="hidden" width="3" height="1">
+    <data><tile gid="2"/><tile/><tile/></data>
+   </layer>
+  </group>
+ </group>
+ <objectgroup id="6" name="objects"/>
+</map>
+"##;
+
+    const TMJ: &str = r##"{
+  "orientation": "orthogonal", "infinite": false, "width": 3, "height": 1,
+  "tilewidth": 2, "tileheight": 4,
+  "properties": [{ "name": "level", "type": "int", "value": 3 }],
+  "tilesets": [
+    { "firstgid": 1, "image": "tiles.png", "tilewidth": 2, "tileheight": 4,
+      "tilecount": 2, "columns": 2,
+      "tiles": [{ "id": 1, "properties": [{ "name": "solid", "type": "bool", "value": true }] }] },
+    { "firstgid": 3, "source": "extra.tsj" }
+  ],
+  "layers": [
+    { "type": "tilelayer", "name": "ground", "width": 3, "height": 1, "data": [1, 0, 3] },
+    { "type": "group", "name": "top", "layers": [
+      { "type": "tilelayer", "name": "items", "width": 3, "height": 1,
+        "encoding": "base64", "compression": "gzip", "data": "ITEMS" },
+      { "type": "tilelayer", "name": "hidden", "width": 3, "height": 1,
+        "visible": false, "data": [2, 0, 0] }
+    ] },
+    { "type": "objectgroup", "name": "objects", "objects": [] }
+  ]
+}"##;
+
+    const TSJ: &str = r#"{
+  "image": "tiles.png", "tilewidth": 2, "tileheight": 4, "tilecount": 2, "columns": 2,
+  "tiles": [
+    { "id": 0, "properties": [{ "name": "name", "value": "spare" }] },
+    { "id": 7, "properties": [{ "name": "name", "value": "lost" }] }
+  ]
+}"#;
+
+    #[test]
+    fn open_maps() {
+        let directory = std::env::temp_dir().join(format!("ti-tiled-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let mut image = RgbaImage::new(4, 4);
+        for y in 0..4 {
+            for x in 0..2 {
+                image.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+            }
+        }
+        image.put_pixel(2, 0, Rgba([0, 255, 0, 255]));
+        image.save(directory.join("tiles.png")).unwrap();
+        fs::write(directory.join("extra.tsj"), TSJ).unwrap();
+        // The middle tile is flipped horizontally, and the last one is beyond all tilesets
+        let items = [0, 2 | 0x8000_0000, 5];
+        fs::write(
+            directory.join("map.tmx"),
+            TMX.replace("ITEMS", &encode(&items, false)),
+        )
+        .unwrap();
+        fs::write(
+            directory.join("map.tmj"),
+            TMJ.replace("ITEMS", &encode(&items, true)),
+        )
+        .unwrap();
+
+        let tmx = Tilemap::open_tiled(directory.join("map.tmx"), ColorMode::TrueColor, true);
+        let tmj = Tilemap::open_tiled(directory.join("map.tmj"), ColorMode::TrueColor, true);
+        fs::remove_dir_all(&directory).unwrap();
+        for map in [tmx.unwrap(), tmj.unwrap()] {
+            assert_eq!(map.tile_size(), (2, 4));
+            assert_eq!(map.tileset().len(), 4);
+            assert_eq!(map.properties.get("level"), Some(&Property::Int(3)));
+            assert_eq!(
+                map.tileset().property(1, "solid"),
+                Some(&Property::Bool(true))
+            );
+            assert_eq!(
+                map.tileset().property(2, "name"),
+                Some(&Property::String("spare".to_string()))
+            );
+            assert_eq!(map.tileset().property(9, "name"), None);
+
+            let names: Vec<_> = map.layers().iter().map(|layer| &layer.name[..]).collect();
+            assert_eq!(names, ["ground", "items", "hidden"]);
+            let ground = map.layer("ground").unwrap();
+            assert_eq!(
+                (ground.get(0, 0), ground.get(1, 0), ground.get(2, 0)),
+                (Some(0), None, Some(2))
+            );
+            let items = map.layer("items").unwrap();
+            assert_eq!(
+                (
+                    items.get(1, 0),
+                    items.get(2, 0),
+                    items.priority,
+                    items.offset
+                ),
+                (Some(1), None, 1, (0, 0))
+            );
+            assert!(!map.layer("hidden").unwrap().visible);
+
+            let mut screen = Screen::new_cells(3, 1);
+            map.draw(&mut screen, 0, 0, Blit::Set);
+            assert_eq!(screen.rasterize(), "⣿⠁⣿\n");
+            assert_eq!(screen.get_color(0, 0), Some(Color::rgb(255, 0, 0)));
+        }
+        let missing = TiledMap::from_tmx(TMX, Path::new("")).map(|_| ());
+        // The external tileset is not in the working directory
+        assert_eq!(missing.unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn tileset_margin() {
+        let directory =
+            std::env::temp_dir().join(format!("ti-tiled-margin-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        // Tiled leaves room for the margin on the left only, so this fits two columns of tiles
+        let mut image = RgbaImage::new(5, 5);
+        image.put_pixel(3, 1, Rgba([0, 255, 0, 255]));
+        image.save(directory.join("tiles.png")).unwrap();
+        fs::write(
+            directory.join("map.tmx"),
+            r#"<map orientation="orthogonal" tilewidth="2" tileheight="4">
+ <tileset firstgid="1" tilewidth="2" tileheight="4" margin="1" tilecount="2" columns="2">
+  <image source="tiles.png" width="5" height="5"/>
+ </tileset>
+ <layer name="ground" width="1" height="1"><data encoding="csv">2</data></layer>
+</map>"#,
+        )
+        .unwrap();
+        let map = Tilemap::open_tiled(directory.join("map.tmx"), ColorMode::TrueColor, true);
+        fs::remove_dir_all(&directory).unwrap();
+        let map = map.unwrap();
+        assert_eq!(map.tileset().len(), 2);
+        let mut screen = Screen::new_cells(1, 1);
+        map.draw(&mut screen, 0, 0, Blit::Set);
+        assert_eq!(screen.rasterize(), "⠁\n");
+        assert_eq!(screen.get_color(0, 0), Some(Color::rgb(0, 255, 0)));
+    }
+
+    #[test]
+    fn properties() {
+        let document = Document::parse(
+            r##"<layer><properties>
+                <property name="sky" type="color" value="#ff00ff00"/>
+                <property name="speed" type="float" value="1.5"/>
+                <property name="note">multiple
+lines</property>
+                <property name="style" type="class" propertytype="Style"/>
+            </properties></layer>"##,
+        )
+        .unwrap();
+        let properties = xml_properties(document.root_element()).unwrap();
+        assert_eq!(
+            properties,
+            Properties::from([
+                ("sky".to_string(), Property::Color(Color::rgb(0, 255, 0))),
+                ("speed".to_string(), Property::Float(1.5)),
+                (
+                    "note".to_string(),
+                    Property::String("multiple\nlines".to_string())
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn invalid_maps() {
+        let tmx = |map: &str| TiledMap::from_tmx(map, Path::new("")).map(|_| ());
+        let tmj = |map: &str| TiledMap::from_tmj(map, Path::new("")).map(|_| ());
+        for result in [
+            tmx("<tileset/>"),
+            tmx(r#"<map orientation="isometric" tilewidth="2" tileheight="4"/>"#),
+            tmx(r#"<map infinite="1" tilewidth="2" tileheight="4"/>"#),
+            tmx(r#"<map tilewidth="2"/>"#),
+            tmx(
+                r#"<map tilewidth="2" tileheight="4"><layer width="1"><data encoding="csv">x</data></layer></map>"#,
+            ),
+            tmx(
+                r#"<map tilewidth="2" tileheight="4"><layer width="1"><data encoding="base64" compression="zstd">AAAA</data></layer></map>"#,
+            ),
+            tmx(
+                r#"<map tilewidth="2" tileheight="4"><tileset firstgid="1" tilewidth="2" tileheight="4"/></map>"#,
+            ),
+            tmj(r#"{ "tilewidth": 2, "tileheight": 4, "infinite": true }"#),
+            tmj(
+                r#"{ "tilewidth": 2, "tileheight": 4, "properties": [{ "name": "a", "type": "int", "value": "b" }] }"#,
+            ),
+            tmj(r#"{ "tilewidth": 2, "tileheight": 4, "tilesets": [{ "image": "tiles.png" }] }"#),
+            tmj(
+                r#"{ "tilewidth": 2, "tileheight": 4, "tilesets": [{ "firstgid": 1, "image": "tiles.png", "tilewidth": 2, "tileheight": 4 }] }"#,
+            ),
+            tmj("[]"),
+            tmx(
+                r#"<map tilewidth="2" tileheight="4"><tileset firstgid="1" tilewidth="2" tileheight="8" tilecount="1" columns="1"><image source="tiles.png"/></tileset></map>"#,
+            ),
+        ] {
+            assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
@@ -4,7 +4,9 @@
 //! Units used in this crate:
 //!
 //! Pixel x/y position/length: u16
+//! Pixel x/y drawing position (may be off screen): i32
 //! Cell x/y position/length: u16
+//! Cell x/y drawing position (may be off screen): i32
 //! Sprite/screen cell index/length: usize
 //! Subcell pixel x/y position/length: u8
 //! Subcell pixel index/offset: u8
@@ -37,6 +39,23 @@ pub(crate) const fn pos_components(x: u16, y: u16) -> ((u16, u8), (u16, u8)) {
     )
 }
 
+/// Converts from a signed (x, y) pixel drawing position to its constituent position components.
+///
+/// Like [`pos_components`], but the cell coordinates are rounded towards negative infinity,
+/// so that the subcell coordinates are always nonnegative.
+pub(crate) const fn signed_pos_components(x: i32, y: i32) -> ((i32, u8), (i32, u8)) {
+    (
+        (
+            x.div_euclid(PIXEL_WIDTH as i32),
+            x.rem_euclid(PIXEL_WIDTH as i32) as u8,
+        ),
+        (
+            y.div_euclid(PIXEL_HEIGHT as i32),
+            y.rem_euclid(PIXEL_HEIGHT as i32) as u8,
+        ),
+    )
+}
+
 /// Converts from a (x, y) position to an array index.
 pub(crate) const fn index(x: u16, y: u16, width: u16) -> usize {
     (y as u32 * width as u32 + x as u32) as usize